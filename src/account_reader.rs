@@ -0,0 +1,78 @@
+use std::io::{Error, ErrorKind, Result};
+
+use solana_program::pubkey::Pubkey;
+
+/// Forward only reader over the Borsh encoded state of the sibling programs.
+/// Fields that are not needed are skipped by their length prefix instead of being decoded,
+/// so reading a few fields of a large account allocates nothing for the rest.
+pub struct AccountDataReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> AccountDataReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        AccountDataReader { data }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(Error::new(ErrorKind::InvalidData, "Unexpected length of input"));
+        }
+
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    }
+
+    pub fn skip(&mut self, len: usize) -> Result<()> {
+        self.take(len).map(|_| ())
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool> {
+        match self.take(1)?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::new(ErrorKind::InvalidData, "Invalid bool representation")),
+        }
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub fn read_pubkey(&mut self) -> Result<Pubkey> {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(self.take(32)?);
+        Ok(Pubkey::new_from_array(bytes))
+    }
+
+    pub fn read_string(&mut self) -> Result<String> {
+        let len = self.read_len()?;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid utf8"))
+    }
+
+    pub fn skip_string(&mut self) -> Result<()> {
+        let len = self.read_len()?;
+        self.skip(len)
+    }
+
+    pub fn skip_strings(&mut self, count: usize) -> Result<()> {
+        for _ in 0..count {
+            self.skip_string()?;
+        }
+        Ok(())
+    }
+
+    pub fn skip_string_vec(&mut self) -> Result<()> {
+        let count = self.read_len()?;
+        self.skip_strings(count)
+    }
+}
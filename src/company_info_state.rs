@@ -4,6 +4,8 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
+use crate::account_reader::AccountDataReader;
+
 #[derive(Debug, PartialEq, Clone)]
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct CompanyInfoState {
@@ -46,4 +48,48 @@ impl IsInitialized for CompanyInfoState {
 
 impl CompanyInfoState {
     pub const LEN: usize = 1+1+32+8+8+32+64+128+64+8+8+32+32+8+128+128+16+32+512+1024+128+128+128+128+128+16+8+8+8; //2946 ~2950
-}
\ No newline at end of file
+}
+
+/// Fields of `CompanyInfoState` needed to verify a company, read without decoding the profile.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CompanyInfoHeader {
+    pub is_initialized: bool, //1
+    pub archived: bool, //1
+    pub user_info_state_account_pubkey: Pubkey, //32
+    pub subscription_plan: String, //16 legacy plan written by the company program, see ImportLegacySubscription
+    pub subscription_purchased_on: u64, //8
+    pub subscription_valid_till: u64, //8
+    pub company_seq_number: String, //8
+}
+impl IsInitialized for CompanyInfoHeader {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl CompanyInfoHeader {
+    /// Reads the header from the Borsh layout of `CompanyInfoState`.
+    pub fn unpack(data: &[u8]) -> std::io::Result<Self> {
+        let mut reader = AccountDataReader::new(data);
+
+        let is_initialized = reader.read_bool()?;
+        let archived = reader.read_bool()?;
+        let user_info_state_account_pubkey = reader.read_pubkey()?;
+        reader.skip(8+8)?; //created_at, updated_at
+        reader.skip_strings(20)?; //username to instagram
+        let subscription_plan = reader.read_string()?;
+        let subscription_purchased_on = reader.read_u64()?;
+        let subscription_valid_till = reader.read_u64()?;
+        let company_seq_number = reader.read_string()?;
+
+        Ok(CompanyInfoHeader {
+            is_initialized,
+            archived,
+            user_info_state_account_pubkey,
+            subscription_plan,
+            subscription_purchased_on,
+            subscription_valid_till,
+            company_seq_number,
+        })
+    }
+}
@@ -0,0 +1,144 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    program_pack::{IsInitialized, Sealed},
+    pubkey::Pubkey,
+};
+
+use crate::subscription_state::SubscriptionPlan;
+
+/// Lamport prices of the subscription plans sold by PurchaseSubscription, 0 when the plan is not for sale.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SubscriptionPrices {
+    pub six_months: u64, //8
+    pub yearly: u64, //8
+    pub forever: u64, //8
+}
+
+impl SubscriptionPrices {
+    pub fn price_of(&self, plan: SubscriptionPlan) -> u64 {
+        match plan {
+            SubscriptionPlan::PayNUse => 0,
+            SubscriptionPlan::SixMonths => self.six_months,
+            SubscriptionPlan::Yearly => self.yearly,
+            SubscriptionPlan::Forever => self.forever,
+        }
+    }
+}
+
+/// SPL mint accepted by PayForApplicationWithToken with the price of an application in it.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PaymentMint {
+    pub mint: Pubkey, //32
+    pub application_price: u64, //8 base units of the mint
+}
+
+#[derive(Debug, PartialEq, Clone)]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ConfigState {
+    pub is_initialized: bool, //1
+    pub authority: Pubkey, //32 admin allowed to change the config
+    pub pending_authority: Pubkey, //32 admin proposed by SetAuthority, default pubkey when no transfer is pending
+    pub payment_authority: Pubkey, //32 signer allowed to record payments with UpdateWorkflowPaymentState
+    pub pending_payment_authority: Pubkey, //32 payment authority proposed by SetAuthority, default pubkey when no transfer is pending
+    pub application_price: u64, //8 lamports charged by PayForApplication, 0 while SOL payments are disabled
+    pub payment_mints: Vec<PaymentMint>, //4+40*4 SPL mints accepted by PayForApplicationWithToken
+    pub subscription_prices: SubscriptionPrices, //24
+    pub user_info_program_id: Pubkey, //32 owner of ApplicantInfoState accounts
+    pub company_info_program_id: Pubkey, //32 owner of CompanyInfoState accounts
+    pub jobpost_info_program_id: Pubkey, //32 owner of JobPostState accounts
+    pub allow_saving_archived_jobs: bool, //1 'saved' workflows may still be added for archived jobs and companies
+    pub bump: u8, //1 canonical bump of the config PDA
+    pub treasury_bump: u8, //1 canonical bump of the treasury PDA
+}
+impl Sealed for ConfigState {}
+impl IsInitialized for ConfigState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl ConfigState {
+    pub const MAX_PAYMENT_MINTS: usize = 4;
+    pub const LEN: usize = 1+32+32+32+32+8+4+40*Self::MAX_PAYMENT_MINTS+24+32+32+32+1+1+1; //424
+
+    pub fn payment_mint(&self, mint: &Pubkey) -> Option<&PaymentMint> {
+        self.payment_mints.iter().find(|payment_mint| payment_mint.mint == *mint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full_config() -> ConfigState {
+        ConfigState {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            pending_authority: Pubkey::new_unique(),
+            payment_authority: Pubkey::new_unique(),
+            pending_payment_authority: Pubkey::new_unique(),
+            application_price: u64::MAX,
+            payment_mints: vec![
+                PaymentMint {
+                    mint: Pubkey::new_unique(),
+                    application_price: u64::MAX,
+                };
+                ConfigState::MAX_PAYMENT_MINTS
+            ],
+            subscription_prices: SubscriptionPrices {
+                six_months: 1,
+                yearly: 2,
+                forever: 3,
+            },
+            user_info_program_id: Pubkey::new_unique(),
+            company_info_program_id: Pubkey::new_unique(),
+            jobpost_info_program_id: Pubkey::new_unique(),
+            allow_saving_archived_jobs: true,
+            bump: 255,
+            treasury_bump: 254,
+        }
+    }
+
+    #[test]
+    fn price_of_every_plan() {
+        let prices = full_config().subscription_prices;
+
+        assert_eq!(prices.price_of(SubscriptionPlan::PayNUse), 0);
+        assert_eq!(prices.price_of(SubscriptionPlan::SixMonths), 1);
+        assert_eq!(prices.price_of(SubscriptionPlan::Yearly), 2);
+        assert_eq!(prices.price_of(SubscriptionPlan::Forever), 3);
+    }
+
+    #[test]
+    fn payment_mint_lookup() {
+        let config = full_config();
+        let payment_mint = config.payment_mints[0];
+
+        assert_eq!(config.payment_mint(&payment_mint.mint), Some(&payment_mint));
+        assert_eq!(config.payment_mint(&Pubkey::new_unique()), None);
+    }
+
+    #[test]
+    fn full_config_fills_the_account() {
+        let config = full_config();
+        let data = config.try_to_vec().unwrap();
+
+        assert_eq!(data.len(), ConfigState::LEN);
+        assert_eq!(ConfigState::try_from_slice(&data).unwrap(), config);
+    }
+
+    #[test]
+    fn config_is_read_from_a_zero_padded_account() {
+        let config = ConfigState {
+            payment_mints: Vec::new(),
+            ..full_config()
+        };
+        let mut data = config.try_to_vec().unwrap();
+        data.resize(ConfigState::LEN, 0);
+
+        let read = solana_program::borsh::try_from_slice_unchecked::<ConfigState>(&data).unwrap();
+        assert_eq!(read, config);
+    }
+}
@@ -2,4 +2,6 @@ pub const WORKFLOW_STATE_ACCOUNT_PREFIX: &'static str = "workflow";
 pub const JOBPOST_STATE_ACCOUNT_PREFIX: &'static str = "jobpost";
 pub const APPLICANT_STATE_ACCOUNT_PREFIX: &'static str = "applicant";
 pub const COMPANY_STATE_ACCOUNT_PREFIX: &'static str = "company";
-pub const SUBSCRIPTION_MODIFIER_PUBKEY: &'static str = "biVmoes7JLLVb6bi5MjWFwc1fzjLK9K19RVxjtE7b6F";
\ No newline at end of file
+pub const CONFIG_STATE_ACCOUNT_PREFIX: &'static str = "config";
+pub const TREASURY_ACCOUNT_PREFIX: &'static str = "treasury";
+pub const SUBSCRIPTION_STATE_ACCOUNT_PREFIX: &'static str = "subscription";
\ No newline at end of file
@@ -1,7 +1,8 @@
 #![cfg(not(feature = "no-entrypoint"))]
-use crate::processor::Processor;
+use crate::{error::WorkflowError, processor::Processor};
 use solana_program::{
-    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult,
+    program_error::PrintProgramError, pubkey::Pubkey,
 };
 
 entrypoint!(process_instruction);
@@ -12,7 +13,11 @@ pub fn process_instruction(
     accounts: &[AccountInfo], // account informations
     instruction_data: &[u8], // Instruction data
 ) -> ProgramResult {
-    Processor::process(program_id, accounts, instruction_data)?;
+    if let Err(error) = Processor::process(program_id, accounts, instruction_data) {
+        // Log the human readable message of custom workflow errors
+        error.print::<WorkflowError>();
+        return Err(error);
+    }
 
     Ok(())
 }
@@ -0,0 +1,166 @@
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+/// Errors returned by the workflow program as `ProgramError::Custom(code)`.
+/// Codes are stable, new variants are only ever appended.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Error, FromPrimitive)]
+pub enum WorkflowError {
+    #[error("Unknown workflow status")]
+    UnknownStatus, //0 stored status is not one of the known workflow statuses
+    #[error("Workflow can only be created as 'saved' or 'applied'")]
+    InvalidInitialStatus, //1
+    #[error("Status transition is not allowed")]
+    InvalidStatusTransition, //2 transition is not allowed by the workflow transition table
+    #[error("Signer is not a participant of the workflow")]
+    NotWorkflowParticipant, //3 signer is neither the candidate nor the company owner of the workflow
+    #[error("Signer is not allowed to set this status")]
+    UnauthorizedStatusChange, //4 signer's role is not allowed to set the requested status
+    #[error("Invalid instruction")]
+    InvalidInstruction, //5
+    #[error("Owner account is not a signer")]
+    OwnerNotSigner, //6
+    #[error("Applicant info state account does not match the derived PDA")]
+    ApplicantPdaMismatch, //7
+    #[error("Applicant info state account is not initialized")]
+    ApplicantNotInitialized, //8
+    #[error("Applicant info state account is not owned by the signer")]
+    ApplicantOwnerMismatch, //9
+    #[error("Company info state account is not initialized")]
+    CompanyNotInitialized, //10
+    #[error("JobPost info state account does not match the derived PDA")]
+    JobPostPdaMismatch, //11
+    #[error("Workflow state account does not match the derived PDA")]
+    WorkflowPdaMismatch, //12
+    #[error("Workflow state account is not owned by the program")]
+    WorkflowNotOwnedByProgram, //13
+    #[error("Workflow state account is not initialized")]
+    WorkflowNotInitialized, //14
+    #[error("Workflow state account does not belong to the job post")]
+    WorkflowJobMismatch, //15
+    #[error("Workflow company owner does not match the company owner")]
+    WorkflowCompanyOwnerMismatch, //16
+    #[error("Unauthorized subscription modifier")]
+    UnauthorizedPaymentModifier, //17
+    #[error("Company info state account data could not be decoded")]
+    InvalidCompanyInfoData, //18
+    #[error("JobPost info state account data could not be decoded")]
+    InvalidJobPostData, //19
+    #[error("Applicant info state account data could not be decoded")]
+    InvalidApplicantInfoData, //20
+    #[error("Workflow state account data could not be decoded")]
+    InvalidWorkflowData, //21
+    #[error("Workflow state account is already initialized")]
+    WorkflowAlreadyInitialized, //22
+    #[error("Workflow can only be closed once it is 'saved', 'withdraw' or 'rejected'")]
+    WorkflowNotClosable, //23
+    #[error("Signer is not allowed to close this workflow")]
+    UnauthorizedClose, //24
+    #[error("Lamport or token amount overflow")]
+    AmountOverflow, //25
+    #[error("Config account does not match the derived PDA")]
+    ConfigPdaMismatch, //26
+    #[error("Config account is already initialized")]
+    ConfigAlreadyInitialized, //27
+    #[error("Config account is not initialized")]
+    ConfigNotInitialized, //28
+    #[error("Config account data could not be decoded")]
+    InvalidConfigData, //29
+    #[error("Signer is not allowed to initialize the config")]
+    UnauthorizedConfigInitializer, //30
+    #[error("Signer is not the config authority")]
+    UnauthorizedConfigAuthority, //31
+    #[error("Signer is not the pending config authority")]
+    PendingAuthorityMismatch, //32
+    #[error("Treasury account does not match the derived PDA")]
+    TreasuryPdaMismatch, //33
+    #[error("Only the company owner can pay for an application")]
+    UnauthorizedPayer, //34
+    #[error("Workflow is already paid")]
+    WorkflowAlreadyPaid, //35
+    #[error("Payment amount must be greater than zero")]
+    InvalidPaymentAmount, //36
+    #[error("Treasury did not receive the payment amount")]
+    PaymentNotReceived, //37
+    #[error("Too many payment mints for the config account")]
+    TooManyPaymentMints, //38
+    #[error("Mint is not accepted for payments")]
+    PaymentMintNotAllowed, //39
+    #[error("Token program account is not the SPL token program")]
+    InvalidTokenProgram, //40
+    #[error("Treasury token account is not owned by the treasury PDA or has another mint")]
+    InvalidTreasuryTokenAccount, //41
+    #[error("Token account data could not be decoded")]
+    InvalidTokenAccountData, //42
+    #[error("Subscription account does not match the derived PDA")]
+    SubscriptionPdaMismatch, //43
+    #[error("Subscription account data could not be decoded")]
+    InvalidSubscriptionData, //44
+    #[error("Subscription plan cannot be purchased")]
+    SubscriptionPlanNotForSale, //45
+    #[error("Only the company owner can purchase a subscription")]
+    UnauthorizedSubscriptionPurchaser, //46
+    #[error("Sibling program accounts do not match the config")]
+    SiblingProgramMismatch, //47
+    #[error("Company info state account is not owned by the company info program")]
+    CompanyNotOwnedBySiblingProgram, //48
+    #[error("JobPost info state account is not owned by the job post program")]
+    JobPostNotOwnedBySiblingProgram, //49
+    #[error("Applicant info state account is not owned by the user info program")]
+    ApplicantNotOwnedBySiblingProgram, //50
+    #[error("Company info state account does not match the PDA of its seq number and owner")]
+    CompanyPdaMismatch, //51
+    #[error("Job post is archived")]
+    JobPostArchived, //52
+    #[error("Company is archived")]
+    CompanyArchived, //53
+    #[error("Workflow state account has an unsupported layout version")]
+    UnsupportedWorkflowVersion, //54
+    #[error("Account is not a workflow state account")]
+    InvalidWorkflowDiscriminator, //55
+    #[error("Only the candidate can change the saved flag of a workflow")]
+    UnauthorizedFlagChange, //56
+    #[error("Rent of a closed workflow cannot be sent to the workflow account itself")]
+    InvalidCloseRecipient, //57
+    #[error("Program data account is not the upgradeable loader data of the program")]
+    InvalidProgramDataAccount, //58
+    #[error("Payment amount does not match the configured application price")]
+    PaymentAmountMismatch, //59
+    #[error("Applications of a company with an active subscription are not paid separately")]
+    CompanySubscribed, //60
+    #[error("Unknown subscription plan")]
+    UnknownSubscriptionPlan, //61
+    #[error("Company has no unexpired subscription to import")]
+    LegacySubscriptionNotActive, //62
+    #[error("Subscription state account already exists")]
+    SubscriptionAlreadyExists, //63
+    #[error("Treasury cannot pay the withdrawn amount and keep its rent exempt minimum")]
+    InsufficientTreasuryBalance, //64
+    #[error("Company already has a subscription that never expires")]
+    SubscriptionNeverExpires, //65
+}
+
+impl From<WorkflowError> for ProgramError {
+    fn from(e: WorkflowError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for WorkflowError {
+    fn type_of() -> &'static str {
+        "WorkflowError"
+    }
+}
+
+impl PrintProgramError for WorkflowError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + num_traits::FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
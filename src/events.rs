@@ -0,0 +1,169 @@
+//! Events logged by the program for indexers.
+//!
+//! Every event is logged with `sol_log_data` as a single `Program data:` entry holding the
+//! Borsh encoding of `WorkflowEvent`. The first byte is the variant index, variants are only
+//! ever appended so existing decoders keep working.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{entrypoint::ProgramResult, log::sol_log_data, pubkey::Pubkey};
+
+use crate::{state::WorkflowState, subscription_state::SubscriptionPlan, workflow_status::WorkflowStatus};
+
+/// Accounts identifying a workflow in every workflow event.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct WorkflowKeys {
+    pub workflow_pubkey: Pubkey, //32 workflow state account
+    pub job_pubkey: Pubkey, //32 job post info state account
+    pub company_pubkey: Pubkey, //32 company info state account
+    pub user_pubkey: Pubkey, //32 applicant info state account of the candidate
+}
+
+impl WorkflowKeys {
+    pub fn of(workflow_pubkey: &Pubkey, workflow_state_data: &WorkflowState) -> Self {
+        WorkflowKeys {
+            workflow_pubkey: *workflow_pubkey,
+            job_pubkey: workflow_state_data.job_pubkey,
+            company_pubkey: workflow_state_data.company_pubkey,
+            user_pubkey: workflow_state_data.user_pubkey,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum WorkflowEvent {
+    /// AddWorkflowState, signed by the candidate.
+    ApplicationCreated {
+        keys: WorkflowKeys,
+        status: WorkflowStatus, //1 => 'saved' or 'applied'
+        actor: Pubkey, //32
+        timestamp: u64, //8
+    },
+    /// UpdateWorkflowState, `old_status` equals `new_status` when only the flags changed.
+    StatusChanged {
+        keys: WorkflowKeys,
+        old_status: WorkflowStatus, //1
+        new_status: WorkflowStatus, //1
+        archived: bool, //1
+        is_saved: bool, //1
+        actor: Pubkey, //32
+        timestamp: u64, //8
+    },
+    /// UpdateWorkflowPaymentState, PayForApplication and PayForApplicationWithToken.
+    PaymentRecorded {
+        keys: WorkflowKeys,
+        is_paid: bool, //1
+        amount: u64, //8 lamports or base units of the mint
+        mint: Pubkey, //32 default pubkey when paid in SOL
+        actor: Pubkey, //32
+        timestamp: u64, //8
+    },
+    /// CloseWorkflowState.
+    WorkflowClosed {
+        keys: WorkflowKeys,
+        status: WorkflowStatus, //1
+        recipient: Pubkey, //32
+        actor: Pubkey, //32
+    },
+    /// MigrateWorkflowState, not logged when the account already had the current layout.
+    WorkflowMigrated {
+        workflow_pubkey: Pubkey, //32
+        from_version: u8, //1
+        to_version: u8, //1
+    },
+    /// PurchaseSubscription, and ImportLegacySubscription with an amount of 0.
+    SubscriptionPurchased {
+        company_pubkey: Pubkey, //32
+        plan: SubscriptionPlan, //1
+        amount: u64, //8 lamports
+        valid_till: u64, //8
+    },
+    /// WithdrawTreasury and WithdrawTreasuryTokens, signed by the config admin.
+    TreasuryWithdrawn {
+        amount: u64, //8 lamports or base units of the mint
+        mint: Pubkey, //32 default pubkey when withdrawn in SOL
+        recipient: Pubkey, //32
+        actor: Pubkey, //32
+    },
+}
+
+impl WorkflowEvent {
+    pub fn emit(&self) -> ProgramResult {
+        let data = self.try_to_vec()?;
+        sol_log_data(&[&data]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> WorkflowKeys {
+        WorkflowKeys {
+            workflow_pubkey: Pubkey::new_unique(),
+            job_pubkey: Pubkey::new_unique(),
+            company_pubkey: Pubkey::new_unique(),
+            user_pubkey: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn variant_indices_never_change() {
+        let events = [
+            WorkflowEvent::ApplicationCreated {
+                keys: keys(),
+                status: WorkflowStatus::Applied,
+                actor: Pubkey::new_unique(),
+                timestamp: 1,
+            },
+            WorkflowEvent::StatusChanged {
+                keys: keys(),
+                old_status: WorkflowStatus::Applied,
+                new_status: WorkflowStatus::InProgress,
+                archived: false,
+                is_saved: true,
+                actor: Pubkey::new_unique(),
+                timestamp: 2,
+            },
+            WorkflowEvent::PaymentRecorded {
+                keys: keys(),
+                is_paid: true,
+                amount: 3,
+                mint: Pubkey::new_unique(),
+                actor: Pubkey::new_unique(),
+                timestamp: 4,
+            },
+            WorkflowEvent::WorkflowClosed {
+                keys: keys(),
+                status: WorkflowStatus::Withdraw,
+                recipient: Pubkey::new_unique(),
+                actor: Pubkey::new_unique(),
+            },
+            WorkflowEvent::WorkflowMigrated {
+                workflow_pubkey: Pubkey::new_unique(),
+                from_version: 1,
+                to_version: 2,
+            },
+            WorkflowEvent::SubscriptionPurchased {
+                company_pubkey: Pubkey::new_unique(),
+                plan: SubscriptionPlan::Yearly,
+                amount: 5,
+                valid_till: 6,
+            },
+            WorkflowEvent::TreasuryWithdrawn {
+                amount: 7,
+                mint: Pubkey::new_unique(),
+                recipient: Pubkey::new_unique(),
+                actor: Pubkey::new_unique(),
+            },
+        ];
+
+        for (index, event) in events.iter().enumerate() {
+            let data = event.try_to_vec().unwrap();
+            assert_eq!(data[0] as usize, index);
+            assert_eq!(&WorkflowEvent::try_from_slice(&data).unwrap(), event);
+        }
+    }
+}
@@ -1,72 +1,697 @@
 #![allow(clippy::too_many_arguments)]
-use borsh::BorshDeserialize;
-use solana_program::{msg, program_error::ProgramError};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    bpf_loader_upgradeable,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
 
-#[derive(BorshDeserialize, Debug)]
-pub struct AddWorkflowStatePayload {
-    pub status: String, //16 => 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
-}
+use crate::{
+    config_state::{PaymentMint, SubscriptionPrices},
+    contants::{
+        APPLICANT_STATE_ACCOUNT_PREFIX, COMPANY_STATE_ACCOUNT_PREFIX, CONFIG_STATE_ACCOUNT_PREFIX,
+        JOBPOST_STATE_ACCOUNT_PREFIX, SUBSCRIPTION_STATE_ACCOUNT_PREFIX, TREASURY_ACCOUNT_PREFIX,
+        WORKFLOW_STATE_ACCOUNT_PREFIX,
+    },
+    error::WorkflowError,
+    subscription_state::SubscriptionPlan,
+    workflow_status::WorkflowStatus,
+};
 
-#[derive(BorshDeserialize, Debug)]
-pub struct UpdateWorkflowStatePayload {
-    pub archived: bool, //1 true when job is in 'accepted' or 'rejected' or 'withdraw' status
-    pub is_saved: bool, //1 true when job is in 'saved' status
-    pub status: String, //16 => 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+/// Bumps of the sibling program PDAs, so existing accounts are verified with
+/// `create_program_address` instead of searching for the bump on every instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SiblingBumps {
+    pub company: u8, //1 company info state account
+    pub jobpost: u8, //1 job post info state account
+    pub applicant: u8, //1 applicant info state account of the candidate
+    pub signer_applicant: u8, //1 applicant info state account of the signer
 }
 
-#[derive(BorshDeserialize, Debug)]
-pub struct UpdateWorkflowPaymentStatePayload {
-    pub is_paid: bool, //1
-    pub paid_amount: u64,//8
+/// Authorities held by the program config account.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityType {
+    /// Admin of the config.
+    Admin,
+    /// Signer of UpdateWorkflowPaymentState.
+    Payment,
 }
 
-#[derive(Clone)]
+/// Instructions of the program, Borsh encoded with the variant index as the leading tag byte.
+/// Variants are only ever appended so the tags of existing instructions never change.
+/// Their payloads did change: the statuses of AddWorkflowState and UpdateWorkflowState are a byte
+/// instead of a string and every workflow instruction ends with its `SiblingBumps`, so data of clients
+/// built against the original payloads is rejected with `InvalidInstruction`.
+///
+/// Instructions operating on a workflow start with the accounts of `validation::WorkflowAccounts`.
+/// Its config state account, at index 9 or at index 10 for UpdateWorkflowPaymentState, is not part
+/// of the original account list of AddWorkflowState, UpdateWorkflowState and UpdateWorkflowPaymentState:
+/// clients built against that list have to append the `config_state_address` PDA.
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
 pub enum WorkflowStateInstruction {
+    /// Signed by the candidate. Workflow accounts, then the subscription PDA of the company.
     AddWorkflowState {
-        status: String, //16 => 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+        status: WorkflowStatus, //1 => 'saved' or 'applied'
+        bumps: SiblingBumps, //4
     },
+    /// Signed by the candidate or the company owner. Workflow accounts only.
     UpdateWorkflowState {
         archived: bool, //1 true when job is in 'accepted' or 'rejected' or 'withdraw' status
         is_saved: bool, //1 true when job is in 'saved' status
-        status: String, //16 => 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+        status: WorkflowStatus, //1 => 'saved' or 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+        bumps: SiblingBumps, //4
     },
+    /// Signed by the payment authority. Workflow accounts with the logged in user account at index 1.
     UpdateWorkflowPaymentState {
         is_paid: bool, //1
         paid_amount: u64,//8
+        bumps: SiblingBumps, //4
+    },
+    /// Sends the rent of an archived workflow to the recipient account and returns the emptied
+    /// account to the system program.
+    /// 'saved' or 'withdraw' workflows are closed by the candidate, 'rejected' ones by the company owner.
+    /// Workflow accounts, then the recipient account.
+    CloseWorkflowState {
+        bumps: SiblingBumps, //4
+    },
+    /// Creates the program config PDA, signed by the upgrade authority of the program which becomes the admin.
+    /// The signer also funds the treasury PDA with its rent exempt minimum, so payments below it can be received.
+    InitializeConfig {
+        payment_authority: Pubkey, //32
+        user_info_program_id: Pubkey, //32
+        company_info_program_id: Pubkey, //32
+        jobpost_info_program_id: Pubkey, //32
+    },
+    /// Signed by the current admin, proposes a new admin or payment authority.
+    SetAuthority {
+        authority_type: AuthorityType, //1
+        new_authority: Pubkey, //32
+    },
+    /// Signed by the proposed authority to complete the transfer.
+    AcceptAuthority {
+        authority_type: AuthorityType, //1
+    },
+    /// Signed by the company owner, moves `amount` lamports into the treasury PDA and marks the workflow paid.
+    /// `amount` has to be the application price of the config and the company must not have an active subscription.
+    /// Workflow accounts, then the subscription PDA of the company and the treasury PDA.
+    PayForApplication {
+        amount: u64, //8 lamports
+        bumps: SiblingBumps, //4
+    },
+    /// Signed by the config admin, replaces the SPL mints accepted for token payments and their prices.
+    SetPaymentMints {
+        mints: Vec<PaymentMint>, //4+40*4
+    },
+    /// Signed by the company owner, moves `amount` tokens of an allowed mint into the treasury token account.
+    /// `amount` has to be the application price of the mint and the company must not have an active subscription.
+    /// Workflow accounts, then the subscription PDA of the company, the source token account,
+    /// the treasury token account, the mint and the token program.
+    PayForApplicationWithToken {
+        amount: u64, //8 base units of the mint
+        bumps: SiblingBumps, //4
+    },
+    /// Signed by the config admin, sets the lamport price of every subscription plan.
+    SetSubscriptionPrices {
+        prices: SubscriptionPrices, //24
+    },
+    /// Signed by the company owner, pays the configured price into the treasury and extends the company subscription.
+    PurchaseSubscription {
+        plan: SubscriptionPlan, //1 => 'sixmonths' or 'yearly' or 'forever'
+        company_bump: u8, //1
+        signer_applicant_bump: u8, //1
+    },
+    /// Signed by the config admin, pins the programs owning the applicant, company and job post accounts.
+    SetSiblingPrograms {
+        user_info_program_id: Pubkey, //32
+        company_info_program_id: Pubkey, //32
+        jobpost_info_program_id: Pubkey, //32
+    },
+    /// Signed by the config admin, lets candidates keep saving archived jobs and companies.
+    SetAllowSavingArchivedJobs {
+        allow: bool, //1
+    },
+    /// Rewrites a workflow state account of the legacy layout in the current one, resizing it
+    /// and topping up its rent from the signer. Anyone can pay for it, current accounts are left untouched.
+    /// A legacy status outside the known ones is migrated as an archived 'saved' workflow.
+    MigrateWorkflowState,
+    /// Signed by the config admin, sets the lamport price of an application paid with PayForApplication.
+    SetApplicationPrice {
+        price: u64, //8
+    },
+    /// Signed by the config admin, creates the subscription PDA of a company from the unexpired plan
+    /// recorded in its company info state account before subscriptions were sold by this program.
+    ImportLegacySubscription {
+        company_bump: u8, //1
+    },
+    /// Signed by the config admin, moves `amount` lamports from the treasury PDA to the recipient account.
+    /// The rent exempt minimum of the treasury is never withdrawn.
+    WithdrawTreasury {
+        amount: u64, //8 lamports
+    },
+    /// Signed by the config admin, moves `amount` tokens from a treasury token account to the recipient token account.
+    WithdrawTreasuryTokens {
+        amount: u64, //8 base units of the mint
     },
 }
 
 impl WorkflowStateInstruction {
     pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
-        let (tag, rest) = input
-            .split_first()
-            .ok_or(ProgramError::InvalidAccountData)?;
+        let tag = input.first().ok_or(WorkflowError::InvalidInstruction)?;
         msg!("Tag received -> {}", tag);
 
-        Ok(match tag {
-            0 => {
-                let payload = AddWorkflowStatePayload::try_from_slice(rest).unwrap();
-
-                Self::AddWorkflowState {
-                    status: payload.status
-                }
-            }
-            1 => {
-                let payload = UpdateWorkflowStatePayload::try_from_slice(rest).unwrap();
-                Self::UpdateWorkflowState  { 
-                    archived: payload.archived,
-                    is_saved: payload.is_saved,
-                    status: payload.status,
-                }
-            }
-            2 => {
-                let payload = UpdateWorkflowPaymentStatePayload::try_from_slice(rest).unwrap();
-                Self::UpdateWorkflowPaymentState  { 
-                    is_paid: payload.is_paid,
-                    paid_amount: payload.paid_amount,
-                }
-            }
-            _ => return Err(ProgramError::InvalidAccountData),
-        })
+        Self::try_from_slice(input).map_err(|_| WorkflowError::InvalidInstruction.into())
+    }
+
+    /// Instruction data read back by `unpack`.
+    pub fn pack(&self) -> Vec<u8> {
+        //writing into a Vec cannot fail
+        self.try_to_vec().unwrap()
+    }
+}
+
+/// Programs owning the applicant, company and job post accounts, as pinned in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SiblingPrograms {
+    pub user_info_program_id: Pubkey,
+    pub company_info_program_id: Pubkey,
+    pub jobpost_info_program_id: Pubkey,
+}
+
+/// Seeds of the sibling accounts of a workflow, the builders derive every PDA and bump from them.
+#[derive(Debug, Clone, Copy)]
+pub struct WorkflowSeeds<'a> {
+    /// Wallet of the candidate.
+    pub candidate: Pubkey,
+    /// Wallet of the company owner.
+    pub company_owner: Pubkey,
+    pub company_seq_number: &'a str,
+    pub job_number: &'a str,
+}
+
+/// Accounts of a workflow derived from its `WorkflowSeeds`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkflowAddresses {
+    pub applicant_info_state: Pubkey,
+    pub company_owner_info_state: Pubkey,
+    pub company_info_state: Pubkey,
+    pub jobpost_info_state: Pubkey,
+    pub workflow_state: Pubkey,
+    applicant_bump: u8,
+    company_owner_bump: u8,
+    company_bump: u8,
+    jobpost_bump: u8,
+}
+
+impl WorkflowAddresses {
+    pub fn derive(program_id: &Pubkey, programs: &SiblingPrograms, seeds: &WorkflowSeeds) -> Self {
+        let (applicant_info_state, applicant_bump) =
+            applicant_info_state_address(&programs.user_info_program_id, &seeds.candidate);
+        let (company_owner_info_state, company_owner_bump) =
+            applicant_info_state_address(&programs.user_info_program_id, &seeds.company_owner);
+        let (company_info_state, company_bump) = Pubkey::find_program_address(
+            &[
+                COMPANY_STATE_ACCOUNT_PREFIX.as_bytes(),
+                seeds.company_seq_number.as_bytes(),
+                company_owner_info_state.as_ref(),
+            ],
+            &programs.company_info_program_id,
+        );
+        let (jobpost_info_state, jobpost_bump) = Pubkey::find_program_address(
+            &[
+                JOBPOST_STATE_ACCOUNT_PREFIX.as_bytes(),
+                seeds.job_number.as_bytes(),
+                company_info_state.as_ref(),
+            ],
+            &programs.jobpost_info_program_id,
+        );
+        let (workflow_state, _bump) = Pubkey::find_program_address(
+            &[
+                WORKFLOW_STATE_ACCOUNT_PREFIX.as_bytes(),
+                jobpost_info_state.as_ref(),
+                applicant_info_state.as_ref(),
+            ],
+            program_id,
+        );
+
+        WorkflowAddresses {
+            applicant_info_state,
+            company_owner_info_state,
+            company_info_state,
+            jobpost_info_state,
+            workflow_state,
+            applicant_bump,
+            company_owner_bump,
+            company_bump,
+            jobpost_bump,
+        }
+    }
+
+    /// Bumps sent along an instruction signed by `signer`.
+    pub fn bumps(&self, seeds: &WorkflowSeeds, signer: &Pubkey) -> SiblingBumps {
+        let signer_applicant = if *signer == seeds.candidate {
+            self.applicant_bump
+        } else if *signer == seeds.company_owner {
+            self.company_owner_bump
+        } else {
+            0
+        };
+
+        SiblingBumps {
+            company: self.company_bump,
+            jobpost: self.jobpost_bump,
+            applicant: self.applicant_bump,
+            signer_applicant,
+        }
+    }
+
+    /// Accounts every workflow instruction starts with, see `validation::WorkflowAccounts`.
+    /// The list ends with the config PDA, which clients of the original account list do not send.
+    fn account_metas(
+        &self,
+        program_id: &Pubkey,
+        programs: &SiblingPrograms,
+        owner: &Pubkey,
+        owner_is_payer: bool,
+        logged_in_user: Option<&Pubkey>,
+    ) -> Vec<AccountMeta> {
+        let mut accounts = vec![if owner_is_payer {
+            AccountMeta::new(*owner, true)
+        } else {
+            AccountMeta::new_readonly(*owner, true)
+        }];
+        if let Some(logged_in_user) = logged_in_user {
+            accounts.push(AccountMeta::new_readonly(*logged_in_user, false));
+        }
+        accounts.extend([
+            AccountMeta::new_readonly(self.company_info_state, false),
+            AccountMeta::new_readonly(self.applicant_info_state, false),
+            AccountMeta::new_readonly(self.jobpost_info_state, false),
+            AccountMeta::new(self.workflow_state, false),
+            AccountMeta::new_readonly(programs.user_info_program_id, false),
+            AccountMeta::new_readonly(programs.company_info_program_id, false),
+            AccountMeta::new_readonly(programs.jobpost_info_program_id, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(config_state_address(program_id), false),
+        ]);
+        accounts
+    }
+}
+
+pub fn config_state_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_STATE_ACCOUNT_PREFIX.as_bytes()], program_id).0
+}
+
+/// ProgramData account of the upgradeable loader holding the upgrade authority of the program.
+pub fn program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+pub fn treasury_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[TREASURY_ACCOUNT_PREFIX.as_bytes()], program_id).0
+}
+
+pub fn subscription_state_address(program_id: &Pubkey, company_info_state: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[SUBSCRIPTION_STATE_ACCOUNT_PREFIX.as_bytes(), company_info_state.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+pub fn applicant_info_state_address(user_info_program_id: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[APPLICANT_STATE_ACCOUNT_PREFIX.as_bytes(), wallet.as_ref()],
+        user_info_program_id,
+    )
+}
+
+/// Signed by the candidate. Sends the config PDA at index 9, see `WorkflowStateInstruction`.
+pub fn add_workflow_state(
+    program_id: &Pubkey,
+    programs: &SiblingPrograms,
+    seeds: &WorkflowSeeds,
+    status: WorkflowStatus,
+) -> Instruction {
+    let addresses = WorkflowAddresses::derive(program_id, programs, seeds);
+    let mut accounts = addresses.account_metas(program_id, programs, &seeds.candidate, true, None);
+    accounts.push(AccountMeta::new_readonly(
+        subscription_state_address(program_id, &addresses.company_info_state),
+        false,
+    ));
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: WorkflowStateInstruction::AddWorkflowState {
+            status,
+            bumps: addresses.bumps(seeds, &seeds.candidate),
+        }
+        .pack(),
+    }
+}
+
+/// Signed by the candidate or the company owner. Sends the config PDA at index 9, see `WorkflowStateInstruction`.
+pub fn update_workflow_state(
+    program_id: &Pubkey,
+    programs: &SiblingPrograms,
+    seeds: &WorkflowSeeds,
+    signer: &Pubkey,
+    archived: bool,
+    is_saved: bool,
+    status: WorkflowStatus,
+) -> Instruction {
+    let addresses = WorkflowAddresses::derive(program_id, programs, seeds);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: addresses.account_metas(program_id, programs, signer, false, None),
+        data: WorkflowStateInstruction::UpdateWorkflowState {
+            archived,
+            is_saved,
+            status,
+            bumps: addresses.bumps(seeds, signer),
+        }
+        .pack(),
+    }
+}
+
+/// Signed by the payment authority of the config, `logged_in_user` is only recorded in the account list.
+/// Sends the config PDA at index 10, see `WorkflowStateInstruction`.
+pub fn update_workflow_payment_state(
+    program_id: &Pubkey,
+    programs: &SiblingPrograms,
+    seeds: &WorkflowSeeds,
+    payment_authority: &Pubkey,
+    logged_in_user: &Pubkey,
+    is_paid: bool,
+    paid_amount: u64,
+) -> Instruction {
+    let addresses = WorkflowAddresses::derive(program_id, programs, seeds);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: addresses.account_metas(program_id, programs, payment_authority, false, Some(logged_in_user)),
+        data: WorkflowStateInstruction::UpdateWorkflowPaymentState {
+            is_paid,
+            paid_amount,
+            bumps: addresses.bumps(seeds, payment_authority),
+        }
+        .pack(),
+    }
+}
+
+/// Signed by the candidate for 'saved' or 'withdraw' workflows, by the company owner for 'rejected' ones.
+pub fn close_workflow_state(
+    program_id: &Pubkey,
+    programs: &SiblingPrograms,
+    seeds: &WorkflowSeeds,
+    signer: &Pubkey,
+    recipient: &Pubkey,
+) -> Instruction {
+    let addresses = WorkflowAddresses::derive(program_id, programs, seeds);
+    let mut accounts = addresses.account_metas(program_id, programs, signer, false, None);
+    accounts.push(AccountMeta::new(*recipient, false));
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: WorkflowStateInstruction::CloseWorkflowState {
+            bumps: addresses.bumps(seeds, signer),
+        }
+        .pack(),
+    }
+}
+
+/// Signed by the company owner.
+pub fn pay_for_application(
+    program_id: &Pubkey,
+    programs: &SiblingPrograms,
+    seeds: &WorkflowSeeds,
+    amount: u64,
+) -> Instruction {
+    let addresses = WorkflowAddresses::derive(program_id, programs, seeds);
+    let mut accounts = addresses.account_metas(program_id, programs, &seeds.company_owner, true, None);
+    accounts.extend([
+        AccountMeta::new_readonly(subscription_state_address(program_id, &addresses.company_info_state), false),
+        AccountMeta::new(treasury_address(program_id), false),
+    ]);
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: WorkflowStateInstruction::PayForApplication {
+            amount,
+            bumps: addresses.bumps(seeds, &seeds.company_owner),
+        }
+        .pack(),
+    }
+}
+
+/// Signed by the company owner, `treasury_token_account` is a token account of `mint` owned by the treasury PDA.
+pub fn pay_for_application_with_token(
+    program_id: &Pubkey,
+    programs: &SiblingPrograms,
+    seeds: &WorkflowSeeds,
+    source_token_account: &Pubkey,
+    treasury_token_account: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let addresses = WorkflowAddresses::derive(program_id, programs, seeds);
+    let mut accounts = addresses.account_metas(program_id, programs, &seeds.company_owner, false, None);
+    accounts.extend([
+        AccountMeta::new_readonly(subscription_state_address(program_id, &addresses.company_info_state), false),
+        AccountMeta::new(*source_token_account, false),
+        AccountMeta::new(*treasury_token_account, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]);
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: WorkflowStateInstruction::PayForApplicationWithToken {
+            amount,
+            bumps: addresses.bumps(seeds, &seeds.company_owner),
+        }
+        .pack(),
+    }
+}
+
+/// Signed by the upgrade authority of the program.
+pub fn initialize_config(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    payment_authority: &Pubkey,
+    programs: &SiblingPrograms,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(config_state_address(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(program_data_address(program_id), false),
+            AccountMeta::new(treasury_address(program_id), false),
+        ],
+        data: WorkflowStateInstruction::InitializeConfig {
+            payment_authority: *payment_authority,
+            user_info_program_id: programs.user_info_program_id,
+            company_info_program_id: programs.company_info_program_id,
+            jobpost_info_program_id: programs.jobpost_info_program_id,
+        }
+        .pack(),
+    }
+}
+
+/// Instructions changing the config take the signing authority and the config account.
+fn config_instruction(program_id: &Pubkey, authority: &Pubkey, instruction: WorkflowStateInstruction) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(config_state_address(program_id), false),
+        ],
+        data: instruction.pack(),
+    }
+}
+
+/// Signed by the config admin.
+pub fn set_authority(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    authority_type: AuthorityType,
+    new_authority: &Pubkey,
+) -> Instruction {
+    config_instruction(
+        program_id,
+        authority,
+        WorkflowStateInstruction::SetAuthority {
+            authority_type,
+            new_authority: *new_authority,
+        },
+    )
+}
+
+/// Signed by the pending admin or payment authority.
+pub fn accept_authority(program_id: &Pubkey, pending_authority: &Pubkey, authority_type: AuthorityType) -> Instruction {
+    config_instruction(program_id, pending_authority, WorkflowStateInstruction::AcceptAuthority { authority_type })
+}
+
+/// Signed by the config admin.
+pub fn set_payment_mints(program_id: &Pubkey, authority: &Pubkey, mints: Vec<PaymentMint>) -> Instruction {
+    config_instruction(program_id, authority, WorkflowStateInstruction::SetPaymentMints { mints })
+}
+
+/// Signed by the config admin.
+pub fn set_application_price(program_id: &Pubkey, authority: &Pubkey, price: u64) -> Instruction {
+    config_instruction(program_id, authority, WorkflowStateInstruction::SetApplicationPrice { price })
+}
+
+/// Signed by the config admin.
+pub fn set_subscription_prices(program_id: &Pubkey, authority: &Pubkey, prices: SubscriptionPrices) -> Instruction {
+    config_instruction(program_id, authority, WorkflowStateInstruction::SetSubscriptionPrices { prices })
+}
+
+/// Signed by the config admin.
+pub fn set_sibling_programs(program_id: &Pubkey, authority: &Pubkey, programs: &SiblingPrograms) -> Instruction {
+    config_instruction(
+        program_id,
+        authority,
+        WorkflowStateInstruction::SetSiblingPrograms {
+            user_info_program_id: programs.user_info_program_id,
+            company_info_program_id: programs.company_info_program_id,
+            jobpost_info_program_id: programs.jobpost_info_program_id,
+        },
+    )
+}
+
+/// Signed by the config admin.
+pub fn set_allow_saving_archived_jobs(program_id: &Pubkey, authority: &Pubkey, allow: bool) -> Instruction {
+    config_instruction(program_id, authority, WorkflowStateInstruction::SetAllowSavingArchivedJobs { allow })
+}
+
+/// Signed by the company owner.
+pub fn purchase_subscription(
+    program_id: &Pubkey,
+    programs: &SiblingPrograms,
+    company_owner: &Pubkey,
+    company_seq_number: &str,
+    plan: SubscriptionPlan,
+) -> Instruction {
+    let (company_owner_info_state, signer_applicant_bump) =
+        applicant_info_state_address(&programs.user_info_program_id, company_owner);
+    let (company_info_state, company_bump) = Pubkey::find_program_address(
+        &[
+            COMPANY_STATE_ACCOUNT_PREFIX.as_bytes(),
+            company_seq_number.as_bytes(),
+            company_owner_info_state.as_ref(),
+        ],
+        &programs.company_info_program_id,
+    );
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*company_owner, true),
+            AccountMeta::new_readonly(company_info_state, false),
+            AccountMeta::new(subscription_state_address(program_id, &company_info_state), false),
+            AccountMeta::new(treasury_address(program_id), false),
+            AccountMeta::new_readonly(config_state_address(program_id), false),
+            AccountMeta::new_readonly(programs.user_info_program_id, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: WorkflowStateInstruction::PurchaseSubscription {
+            plan,
+            company_bump,
+            signer_applicant_bump,
+        }
+        .pack(),
+    }
+}
+
+/// Signed by any payer of the rent top-up.
+pub fn migrate_workflow_state(program_id: &Pubkey, payer: &Pubkey, workflow_state: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*workflow_state, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: WorkflowStateInstruction::MigrateWorkflowState.pack(),
+    }
+}
+
+/// Signed by the config admin, who also pays the rent of the subscription account.
+pub fn import_legacy_subscription(
+    program_id: &Pubkey,
+    programs: &SiblingPrograms,
+    authority: &Pubkey,
+    company_owner: &Pubkey,
+    company_seq_number: &str,
+) -> Instruction {
+    let (company_owner_info_state, _) = applicant_info_state_address(&programs.user_info_program_id, company_owner);
+    let (company_info_state, company_bump) = Pubkey::find_program_address(
+        &[
+            COMPANY_STATE_ACCOUNT_PREFIX.as_bytes(),
+            company_seq_number.as_bytes(),
+            company_owner_info_state.as_ref(),
+        ],
+        &programs.company_info_program_id,
+    );
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(company_info_state, false),
+            AccountMeta::new(subscription_state_address(program_id, &company_info_state), false),
+            AccountMeta::new_readonly(config_state_address(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: WorkflowStateInstruction::ImportLegacySubscription { company_bump }.pack(),
+    }
+}
+
+/// Signed by the config admin.
+pub fn withdraw_treasury(program_id: &Pubkey, authority: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(config_state_address(program_id), false),
+            AccountMeta::new(treasury_address(program_id), false),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: WorkflowStateInstruction::WithdrawTreasury { amount }.pack(),
+    }
+}
+
+/// Signed by the config admin, `treasury_token_account` is held by the treasury PDA.
+pub fn withdraw_treasury_tokens(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    treasury_token_account: &Pubkey,
+    recipient_token_account: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(config_state_address(program_id), false),
+            AccountMeta::new_readonly(treasury_address(program_id), false),
+            AccountMeta::new(*treasury_token_account, false),
+            AccountMeta::new(*recipient_token_account, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: WorkflowStateInstruction::WithdrawTreasuryTokens { amount }.pack(),
     }
 }
@@ -4,6 +4,8 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
+use crate::account_reader::AccountDataReader;
+
 #[derive(Debug, PartialEq, Clone)]
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct JobPostState {
@@ -40,3 +42,46 @@ impl IsInitialized for JobPostState {
 impl JobPostState {
     pub const LEN: usize = 1+1+32+32+8+8+128+256+1024+148+16+8+8+8+8+8+660+512+32+64+64+8; //4058 ~4100
 }
+
+/// Fields of `JobPostState` needed to verify a job post, read without decoding the descriptions.
+#[derive(Debug, PartialEq, Clone)]
+pub struct JobPostHeader {
+    pub is_initialized: bool, //1
+    pub archived: bool, //1
+    pub owner_pubkey: Pubkey, //32
+    pub company_pubkey: Pubkey, //32
+    pub job_number: String, //8
+}
+impl IsInitialized for JobPostHeader {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl JobPostHeader {
+    /// Reads the header from the Borsh layout of `JobPostState`.
+    pub fn unpack(data: &[u8]) -> std::io::Result<Self> {
+        let mut reader = AccountDataReader::new(data);
+
+        let is_initialized = reader.read_bool()?;
+        let archived = reader.read_bool()?;
+        let owner_pubkey = reader.read_pubkey()?;
+        let company_pubkey = reader.read_pubkey()?;
+        reader.skip(8+8)?; //created_at, updated_at
+        reader.skip_strings(3)?; //job_title, short_description, long_description
+        reader.skip_string_vec()?; //category
+        reader.skip_strings(3)?; //job_type, currency_type, currency
+        reader.skip(8+8+8)?; //min_salary, max_salary, experience_in_months
+        reader.skip_string_vec()?; //skills
+        reader.skip_strings(4)?; //qualification, job_location_type, country, city
+        let job_number = reader.read_string()?;
+
+        Ok(JobPostHeader {
+            is_initialized,
+            archived,
+            owner_pubkey,
+            company_pubkey,
+            job_number,
+        })
+    }
+}
@@ -1,8 +1,15 @@
 pub mod entrypoint;
+pub mod error;
 pub mod instruction;
 pub mod processor;
 pub mod state;
+pub mod validation;
+pub mod subscription_state;
 pub mod contants;
 pub mod company_info_state;
+pub mod config_state;
 pub mod applicant_info_state;
-pub mod jobpost_info_state;
\ No newline at end of file
+pub mod jobpost_info_state;
+pub mod workflow_status;
+pub mod account_reader;
+pub mod events;
\ No newline at end of file
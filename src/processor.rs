@@ -2,21 +2,34 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
-    program::{invoke_signed},
-    program_error::ProgramError,
-    program_pack::{IsInitialized},
+    program::{invoke, invoke_signed},
+    program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
-    system_instruction::create_account,
+    system_instruction::{allocate, assign, create_account, transfer},
+    system_program,
     sysvar::{rent::Rent, Sysvar}, borsh::try_from_slice_unchecked, clock::Clock,
 };
+use spl_token::{
+    instruction::transfer_checked,
+    state::{Account as TokenAccount, Mint},
+};
 // use mpl_candy_machine::state::CandyMachine;
+use std::str::FromStr;
 use borsh::{BorshSerialize};
 use crate::{
-    instruction::{WorkflowStateInstruction},
-    state::{WorkflowState},
-    company_info_state::{CompanyInfoState},
-    applicant_info_state::ApplicantInfoState, contants::{WORKFLOW_STATE_ACCOUNT_PREFIX, APPLICANT_STATE_ACCOUNT_PREFIX, JOBPOST_STATE_ACCOUNT_PREFIX, SUBSCRIPTION_MODIFIER_PUBKEY}, jobpost_info_state::JobPostState
-    
+    error::WorkflowError,
+    events::{WorkflowEvent, WorkflowKeys},
+    instruction::{AuthorityType, SiblingBumps, WorkflowStateInstruction},
+    workflow_status::{WorkflowRole, WorkflowStatus},
+    state::{WorkflowState, WorkflowStateV1},
+    config_state::{ConfigState, PaymentMint, SubscriptionPrices},
+    subscription_state::{SubscriptionPlan, SubscriptionState},
+    contants::{WORKFLOW_STATE_ACCOUNT_PREFIX, APPLICANT_STATE_ACCOUNT_PREFIX, CONFIG_STATE_ACCOUNT_PREFIX, TREASURY_ACCOUNT_PREFIX, SUBSCRIPTION_STATE_ACCOUNT_PREFIX},
+    validation::{
+        active_subscription, load_company, load_config_as_admin, load_config_state, load_subscription_state,
+        treasury_pda, verify_open_for_applications, verify_received_amount, verify_treasury,
+        verify_upgrade_authority, ValidationMode, WorkflowAccounts,
+    },
 };
 pub struct Processor;
 impl Processor {
@@ -28,35 +41,154 @@ impl Processor {
         let instruction = WorkflowStateInstruction::unpack(instruction_data)?;
         match instruction {
             WorkflowStateInstruction::AddWorkflowState{
-                status, //16 => 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+                status, //1 => 'saved' or 'applied'
+                bumps, //4
             } => {
                 msg!("Instruction: Add Workflow State");
                 return Self::add_workflow_state(accounts, program_id, 
-                    status, //16 => 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+                    status, //1 => 'saved' or 'applied'
+                    bumps,
                 );
             }
             WorkflowStateInstruction::UpdateWorkflowState{
                 archived, //1 true when job is in 'accepted' or 'rejected' or 'withdraw' status
                 is_saved, //1 true when user saves the job
-                status, //16 => 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+                status, //1 => 'saved' or 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+                bumps, //4
             } => {
                 msg!("Instruction: Update Workflow State");
                 return Self::update_workflow_state(accounts, program_id, 
                     archived, //1 true when job is in 'accepted' or 'rejected' or 'withdraw' status
                     is_saved,
-                    status, //16 => 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+                    status, //1 => 'saved' or 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+                    bumps,
                 );
             }
             WorkflowStateInstruction::UpdateWorkflowPaymentState{
                 is_paid, //1
                 paid_amount,//8
+                bumps, //4
             } => {
                 msg!("Instruction: Update Workflow Payment State");
                 return Self::update_workflow_payment_state(accounts, program_id, 
                     is_paid, //1
                     paid_amount,//8
+                    bumps,
+                );
+            }
+            WorkflowStateInstruction::CloseWorkflowState{
+                bumps, //4
+            } => {
+                msg!("Instruction: Close Workflow State");
+                return Self::close_workflow_state(accounts, program_id, bumps);
+            }
+            WorkflowStateInstruction::InitializeConfig{
+                payment_authority, //32
+                user_info_program_id, //32
+                company_info_program_id, //32
+                jobpost_info_program_id, //32
+            } => {
+                msg!("Instruction: Initialize Config");
+                return Self::initialize_config(accounts, program_id,
+                    payment_authority,
+                    user_info_program_id,
+                    company_info_program_id,
+                    jobpost_info_program_id,
                 );
             }
+            WorkflowStateInstruction::SetAuthority{
+                authority_type, //1
+                new_authority, //32
+            } => {
+                msg!("Instruction: Set Authority");
+                return Self::set_authority(accounts, program_id, authority_type, new_authority);
+            }
+            WorkflowStateInstruction::AcceptAuthority{
+                authority_type, //1
+            } => {
+                msg!("Instruction: Accept Authority");
+                return Self::accept_authority(accounts, program_id, authority_type);
+            }
+            WorkflowStateInstruction::PayForApplication{
+                amount, //8
+                bumps, //4
+            } => {
+                msg!("Instruction: Pay For Application");
+                return Self::pay_for_application(accounts, program_id, amount, bumps);
+            }
+            WorkflowStateInstruction::SetPaymentMints{
+                mints, //4+32*4
+            } => {
+                msg!("Instruction: Set Payment Mints");
+                return Self::set_payment_mints(accounts, program_id, mints);
+            }
+            WorkflowStateInstruction::PayForApplicationWithToken{
+                amount, //8
+                bumps, //4
+            } => {
+                msg!("Instruction: Pay For Application With Token");
+                return Self::pay_for_application_with_token(accounts, program_id, amount, bumps);
+            }
+            WorkflowStateInstruction::SetSubscriptionPrices{
+                prices, //24
+            } => {
+                msg!("Instruction: Set Subscription Prices");
+                return Self::set_subscription_prices(accounts, program_id, prices);
+            }
+            WorkflowStateInstruction::PurchaseSubscription{
+                plan, //1
+                company_bump, //1
+                signer_applicant_bump, //1
+            } => {
+                msg!("Instruction: Purchase Subscription");
+                return Self::purchase_subscription(accounts, program_id, plan, company_bump, signer_applicant_bump);
+            }
+            WorkflowStateInstruction::SetSiblingPrograms{
+                user_info_program_id, //32
+                company_info_program_id, //32
+                jobpost_info_program_id, //32
+            } => {
+                msg!("Instruction: Set Sibling Programs");
+                return Self::set_sibling_programs(accounts, program_id,
+                    user_info_program_id,
+                    company_info_program_id,
+                    jobpost_info_program_id,
+                );
+            }
+            WorkflowStateInstruction::SetAllowSavingArchivedJobs{
+                allow, //1
+            } => {
+                msg!("Instruction: Set Allow Saving Archived Jobs");
+                return Self::set_allow_saving_archived_jobs(accounts, program_id, allow);
+            }
+            WorkflowStateInstruction::MigrateWorkflowState => {
+                msg!("Instruction: Migrate Workflow State");
+                return Self::migrate_workflow_state(accounts, program_id);
+            }
+            WorkflowStateInstruction::SetApplicationPrice{
+                price, //8
+            } => {
+                msg!("Instruction: Set Application Price");
+                return Self::set_application_price(accounts, program_id, price);
+            }
+            WorkflowStateInstruction::ImportLegacySubscription{
+                company_bump, //1
+            } => {
+                msg!("Instruction: Import Legacy Subscription");
+                return Self::import_legacy_subscription(accounts, program_id, company_bump);
+            }
+            WorkflowStateInstruction::WithdrawTreasury{
+                amount, //8
+            } => {
+                msg!("Instruction: Withdraw Treasury");
+                return Self::withdraw_treasury(accounts, program_id, amount);
+            }
+            WorkflowStateInstruction::WithdrawTreasuryTokens{
+                amount, //8
+            } => {
+                msg!("Instruction: Withdraw Treasury Tokens");
+                return Self::withdraw_treasury_tokens(accounts, program_id, amount);
+            }
         }
     }
 
@@ -64,520 +196,1270 @@ impl Processor {
     pub fn add_workflow_state(
         accounts: &[AccountInfo],
         program_id: &Pubkey,
-        status: String, //16 => 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+        status: WorkflowStatus, //1 => 'saved' or 'applied'
+        bumps: SiblingBumps,
     ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-
         msg!("Add Workflow State of Job");
-        let owner_account = next_account_info(account_info_iter)?;
-        let company_info_state_account = next_account_info(account_info_iter)?;
-        let applicant_info_state_account = next_account_info(account_info_iter)?;
-        let jobpost_info_state_account = next_account_info(account_info_iter)?;
-        let workflow_info_state_account = next_account_info(account_info_iter)?;
+        if !status.is_initial() {
+            msg!("Workflow can only be created with 'saved' or 'applied' status, received '{}'", status.as_str());
+            return Err(WorkflowError::InvalidInitialStatus.into());
+        }
 
-        let user_info_program_id = next_account_info(account_info_iter)?;
-        let _company_info_program_id: &AccountInfo = next_account_info(account_info_iter)?;
-        let jobpost_info_program_id: &AccountInfo = next_account_info(account_info_iter)?;
-        let system_program_id = next_account_info(account_info_iter)?;
+        let WorkflowAccounts {
+            owner_account,
+            company_info_state_account,
+            applicant_info_state_account,
+            jobpost_info_state_account,
+            workflow_info_state_account,
+            system_program_id,
+            remaining_accounts,
+            config_state_data,
+            company_info_state_data,
+            jobpost_info_state_data,
+            mut workflow_state_data,
+            workflow_nonce,
+            ..
+        } = WorkflowAccounts::load(program_id, accounts, ValidationMode::Create, &bumps)?;
+
+        let account_info_iter = &mut remaining_accounts.iter();
+        let subscription_state_account = next_account_info(account_info_iter)?;
+
+        verify_open_for_applications(
+            &jobpost_info_state_data,
+            &company_info_state_data,
+            status,
+            config_state_data.allow_saving_archived_jobs,
+        )?;
+
+        if workflow_info_state_account.owner != program_id {
+            msg!("Creating Workflow State Account");
+            Self::create_pda_account(
+                owner_account,
+                workflow_info_state_account,
+                system_program_id,
+                program_id,
+                WorkflowState::LEN,
+                &[
+                    WORKFLOW_STATE_ACCOUNT_PREFIX.as_bytes(),
+                    jobpost_info_state_account.key.as_ref(),
+                    applicant_info_state_account.key.as_ref(),
+                    &[workflow_nonce],
+                ],
+            )?;
 
-        //State: Verify Applicant state account
-        let applicant_info_pda_prefix = APPLICANT_STATE_ACCOUNT_PREFIX;
+            msg!("Workflow State Account Created");
+        }
 
-        let applicant_pda_seed = &[
-            applicant_info_pda_prefix.as_bytes(),
-            owner_account.key.as_ref()
-        ];
-        let (applicant_info_pda, _nonce) =
-            Pubkey::find_program_address(applicant_pda_seed, user_info_program_id.key);
+        workflow_state_data.discriminator = WorkflowState::DISCRIMINATOR;
+        workflow_state_data.version = WorkflowState::VERSION;
+        workflow_state_data.is_initialized = true;
+        workflow_state_data.archived = false;
+        workflow_state_data.is_saved = status == WorkflowStatus::Saved;
+        workflow_state_data.status = status;
+        workflow_state_data.company_owner_pubkey = company_info_state_data.user_info_state_account_pubkey;
+        workflow_state_data.company_pubkey = *company_info_state_account.key;
+        workflow_state_data.user_pubkey = *applicant_info_state_account.key;
+        workflow_state_data.job_pubkey = *jobpost_info_state_account.key;
+        workflow_state_data.job_applied_at = Clock::get()?.unix_timestamp as u64 * 1000;
 
-        if applicant_info_pda != *applicant_info_state_account.key{
-            msg!("Invalid Applicant Info State Account");
-            return Err(ProgramError::InvalidAccountData);
+        //check for an active subscription purchased through this program,
+        //the plan in the company info state account is not backed by a verifiable payment
+        let timestamp = Clock::get()?.unix_timestamp as u64 * 1000;
+        let (subscription_status, subscription_purchased_at) = match active_subscription(
+            program_id,
+            company_info_state_account,
+            subscription_state_account,
+            timestamp,
+        )? {
+            Some(subscription) => {
+                msg!("Subscription Valid till: {}", subscription.valid_till);
+                (true, subscription.purchased_on)
+            }
+            None => (false, 0),
+        };
+
+        workflow_state_data.is_paid = subscription_status;
+        workflow_state_data.paid_amount = 0;
+        workflow_state_data.paid_mint = Pubkey::default();
+        workflow_state_data.paid_at = subscription_purchased_at;
+        workflow_state_data.bump = workflow_nonce;
+        workflow_state_data.updated_at = Clock::get()?.unix_timestamp as u64 * 1000;
+        workflow_state_data.record_status(status, *owner_account.key, workflow_state_data.updated_at);
+        workflow_state_data.serialize(&mut &mut workflow_info_state_account.data.borrow_mut()[..])?;
+
+        WorkflowEvent::ApplicationCreated {
+            keys: WorkflowKeys::of(workflow_info_state_account.key, &workflow_state_data),
+            status,
+            actor: *owner_account.key,
+            timestamp: workflow_state_data.updated_at,
         }
+        .emit()?;
 
-        //End: Verify Applicant state account
+        msg!("Workflow State Account data added");
 
-        //State: Verify Company Info state account
-        let company_info_state_data = 
-        try_from_slice_unchecked::<CompanyInfoState>(&company_info_state_account.data.borrow()).unwrap();
+        Ok(())
+    }
 
+    pub fn update_workflow_state(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        archived: bool, //1 true when job is in 'accepted' or 'rejected' or 'withdraw' status
+        is_saved: bool, //1 true when user saves the job
+        status: WorkflowStatus, //1 => 'saved' or 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+        bumps: SiblingBumps,
+    ) -> ProgramResult {
+        msg!("Updating Workflow Info");
+        let WorkflowAccounts {
+            owner_account,
+            workflow_info_state_account,
+            config_state_data,
+            company_info_state_data,
+            jobpost_info_state_data,
+            mut workflow_state_data,
+            is_candidate,
+            is_company,
+            ..
+        } = WorkflowAccounts::load(program_id, accounts, ValidationMode::Participant, &bumps)?;
+
+        let current_status = workflow_state_data.status;
+
+        if !current_status.can_transition_to(status) {
+            msg!("Workflow status cannot move from '{}' to '{}'", current_status.as_str(), status.as_str());
+            return Err(WorkflowError::InvalidStatusTransition.into());
+        }
 
-        // let company_info_pda_prefix = COMPANY_STATE_ACCOUNT_PREFIX;
+        //The saved flag is the candidate's bookmark, either side may archive the workflow
+        if is_saved != workflow_state_data.is_saved && !is_candidate {
+            msg!("Only the candidate can change the saved flag");
+            return Err(WorkflowError::UnauthorizedFlagChange.into());
+        }
 
-        // let company_info_pda_seed = &[
-        //     company_info_pda_prefix.as_bytes(),
-        //     company_info_state_data.company_seq_number.as_bytes(),
-        //     applicant_info_state_account.key.as_ref()
-        // ];
+        //Re-sending the current status only updates the flags, it is not a stage of the application
+        if status != current_status {
+            let allowed = (is_candidate && status.can_be_set_by(WorkflowRole::Candidate))
+                || (is_company && status.can_be_set_by(WorkflowRole::Company));
+            if !allowed {
+                msg!("Signer is not allowed to move the workflow to '{}'", status.as_str());
+                return Err(WorkflowError::UnauthorizedStatusChange.into());
+            }
+        }
 
-        // let (company_info_pda, _nonce) =
-        //     Pubkey::find_program_address(company_info_pda_seed, company_info_program_id.key);
+        //A saved job can only be applied to while the job post and the company are open
+        if status == WorkflowStatus::Applied && current_status != WorkflowStatus::Applied {
+            verify_open_for_applications(
+                &jobpost_info_state_data,
+                &company_info_state_data,
+                status,
+                config_state_data.allow_saving_archived_jobs,
+            )?;
+        }
 
-        // if company_info_pda != *company_info_state_account.key{
-        //     msg!("Invalid Company Info State Account");
-        //     return Err(ProgramError::InvalidAccountData);
-        // }
+        workflow_state_data.archived = archived;
+        workflow_state_data.is_saved = is_saved;
+        workflow_state_data.status = status;
+        workflow_state_data.updated_at = Clock::get()?.unix_timestamp as u64 * 1000;
 
-        if !company_info_state_data.is_initialized() {
-            msg!("Company info state account is not initialized");
-            return Err(ProgramError::UninitializedAccount);
+        if status != current_status {
+            workflow_state_data.record_status(status, *owner_account.key, workflow_state_data.updated_at);
         }
 
-        // if company_info_state_data.user_info_state_account_pubkey != *applicant_info_state_account.key{
-        //     msg!("company_info_state_account does not match the user_info_state_account_pubkey of the company_info_state_account");
-        //     return Err(ProgramError::InvalidAccountData);
-        // }
+        workflow_state_data.serialize(&mut &mut workflow_info_state_account.data.borrow_mut()[..])?;
 
-        //End: Verify Company Info state account
+        WorkflowEvent::StatusChanged {
+            keys: WorkflowKeys::of(workflow_info_state_account.key, &workflow_state_data),
+            old_status: current_status,
+            new_status: status,
+            archived,
+            is_saved,
+            actor: *owner_account.key,
+            timestamp: workflow_state_data.updated_at,
+        }
+        .emit()?;
 
-        //State: Verify Jobpost Info state account
-        let jobpost_info_state_data =
-        try_from_slice_unchecked::<JobPostState>(&jobpost_info_state_account.data.borrow()).unwrap();
-        
-        let jobpost_info_pda_prefix = JOBPOST_STATE_ACCOUNT_PREFIX;
+        msg!("Workflow State Account data updated");
 
-        let jobpost_info_pda = &[
-            jobpost_info_pda_prefix.as_bytes(),
-            jobpost_info_state_data.job_number.as_bytes(),
-            company_info_state_account.key.as_ref(),
-        ];
+        Ok(())
+    }
 
-        let (jobpost_info_pda, _nonce) =
-            Pubkey::find_program_address(jobpost_info_pda, jobpost_info_program_id.key);
+    pub fn update_workflow_payment_state(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        is_paid: bool,
+        paid_amount: u64,
+        bumps: SiblingBumps,
+    ) -> ProgramResult{
+        msg!("Updating Workflow Payment Info");
+        let WorkflowAccounts {
+            owner_account,
+            workflow_info_state_account,
+            mut workflow_state_data,
+            ..
+        } = WorkflowAccounts::load(program_id, accounts, ValidationMode::PaymentAuthority, &bumps)?;
 
-        if jobpost_info_pda != *jobpost_info_state_account.key{
-            msg!("JobPost Info State Account does not match the derived PDA");
-            return Err(ProgramError::InvalidSeeds);
+        workflow_state_data.is_paid = is_paid;
+        workflow_state_data.paid_amount = paid_amount;
+        workflow_state_data.paid_at = Clock::get()?.unix_timestamp as u64 * 1000;
+        workflow_state_data.updated_at = Clock::get()?.unix_timestamp as u64 * 1000;
+
+        workflow_state_data.serialize(&mut &mut workflow_info_state_account.data.borrow_mut()[..])?;
+
+        WorkflowEvent::PaymentRecorded {
+            keys: WorkflowKeys::of(workflow_info_state_account.key, &workflow_state_data),
+            is_paid,
+            amount: paid_amount,
+            mint: workflow_state_data.paid_mint,
+            actor: *owner_account.key,
+            timestamp: workflow_state_data.updated_at,
         }
-        //Send: Verify Jobpost Info state account
+        .emit()?;
 
-        if owner_account.is_signer == false {
-            msg!("Owner account is not a signer");
-            return Err(ProgramError::MissingRequiredSignature);
+        msg!("Workflow State Account payment staus updated");
+
+        Ok(())
+    }
+
+    pub fn close_workflow_state(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        bumps: SiblingBumps,
+    ) -> ProgramResult {
+        msg!("Closing Workflow State");
+        let WorkflowAccounts {
+            owner_account,
+            workflow_info_state_account,
+            remaining_accounts,
+            workflow_state_data,
+            is_candidate,
+            is_company,
+            ..
+        } = WorkflowAccounts::load(program_id, accounts, ValidationMode::Participant, &bumps)?;
+
+        let account_info_iter = &mut remaining_accounts.iter();
+        let recipient_account = next_account_info(account_info_iter)?;
+
+        if recipient_account.key == workflow_info_state_account.key {
+            msg!("Rent of the workflow cannot be sent back to the workflow account");
+            return Err(WorkflowError::InvalidCloseRecipient.into());
         }
 
-        let wokrflow_state_pda_prefix = WORKFLOW_STATE_ACCOUNT_PREFIX;
+        let status = workflow_state_data.status;
 
-        let wokrflow_state_pda_seed = &[
-            wokrflow_state_pda_prefix.as_bytes(),
-            jobpost_info_state_account.key.as_ref(),
-            applicant_info_state_account.key.as_ref(),
-        ];
+        let closing_role = match status {
+            WorkflowStatus::Saved | WorkflowStatus::Withdraw => WorkflowRole::Candidate,
+            WorkflowStatus::Rejected => WorkflowRole::Company,
+            _ => {
+                msg!("Workflow in '{}' status cannot be closed", status.as_str());
+                return Err(WorkflowError::WorkflowNotClosable.into());
+            }
+        };
 
-        let (wokrflow_state_pda, nonce) =
-            Pubkey::find_program_address(wokrflow_state_pda_seed, program_id);
+        let is_authorized = match closing_role {
+            WorkflowRole::Candidate => is_candidate,
+            WorkflowRole::Company => is_company,
+        };
 
-        if wokrflow_state_pda != *workflow_info_state_account.key{
-            msg!("Invalid Workflow State PDA");
-            return Err(ProgramError::InvalidSeeds);
+        if !is_authorized {
+            msg!("Signer is not allowed to close a workflow in '{}' status", status.as_str());
+            return Err(WorkflowError::UnauthorizedClose.into());
         }
 
-        let applicant_info_state_data = 
-        try_from_slice_unchecked::<ApplicantInfoState>(&applicant_info_state_account.data.borrow()).unwrap();
+        //Move all lamports out and hand the emptied account back to the system program,
+        //so it cannot be revived as a workflow within the same transaction
+        let workflow_lamports = workflow_info_state_account.lamports();
+        **recipient_account.lamports.borrow_mut() = recipient_account
+            .lamports()
+            .checked_add(workflow_lamports)
+            .ok_or(WorkflowError::AmountOverflow)?;
+        **workflow_info_state_account.lamports.borrow_mut() = 0;
+        workflow_info_state_account.realloc(0, false)?;
+        workflow_info_state_account.assign(&system_program::id());
+
+        WorkflowEvent::WorkflowClosed {
+            keys: WorkflowKeys::of(workflow_info_state_account.key, &workflow_state_data),
+            status,
+            recipient: *recipient_account.key,
+            actor: *owner_account.key,
+        }
+        .emit()?;
+
+        msg!("Workflow State Account closed");
 
-        if !company_info_state_data.is_initialized() {
-            msg!("Company info state account is not initialized");
-            return Err(ProgramError::UninitializedAccount);
+        Ok(())
+    }
+
+    pub fn pay_for_application(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        amount: u64,
+        bumps: SiblingBumps,
+    ) -> ProgramResult {
+        msg!("Paying For Application");
+        if amount == 0 {
+            msg!("Payment amount must be greater than zero");
+            return Err(WorkflowError::InvalidPaymentAmount.into());
         }
 
-        //If any of the conditions are not met, the transaction will fail
-        //if 1. condition is true that means applicant_info_state_account is not the correct owner of the company_info_state_account
-        //if 2. condition is true that means owner_account is not the correct owner of the applicant_info_state_account
-        if company_info_state_data.user_info_state_account_pubkey != *applicant_info_state_account.key && applicant_info_state_data.owner_pubkey != *owner_account.key{
-            msg!("1. user_info_state_account_pubkey of the company_info_state_account does not match the applicant_info_state_account");
-            msg!("2. owner_pubkey of the applicant_info_state_data does not match the owner_account of the transaction");
-            return Err(ProgramError::InvalidAccountData);
+        let WorkflowAccounts {
+            owner_account,
+            workflow_info_state_account,
+            system_program_id,
+            remaining_accounts,
+            config_state_data,
+            mut workflow_state_data,
+            ..
+        } = WorkflowAccounts::load(program_id, accounts, ValidationMode::CompanyPayer, &bumps)?;
+
+        if amount != config_state_data.application_price {
+            msg!("Application price is {} lamports, got {}", config_state_data.application_price, amount);
+            return Err(WorkflowError::PaymentAmountMismatch.into());
         }
 
-        
-        if workflow_info_state_account.owner != program_id {
-            msg!("Creating Workflow State Account");
-            invoke_signed(
-                &create_account(
-                    owner_account.key,
-                    workflow_info_state_account.key,
-                    Rent::default().minimum_balance(WorkflowState::LEN),
-                    WorkflowState::LEN as u64,
-                    program_id,
-                ),
-                &[
-                    owner_account.clone(),              //payer of the account - owner
-                    workflow_info_state_account.clone(), //account to be created
-                    company_info_state_account.clone(), //state account key pair of the program id created by owner
-                    system_program_id.clone(), // always prefer to send from outside which is use to create the account
-                ],
-                &[&[
-                    wokrflow_state_pda_prefix.as_bytes(),
-                    jobpost_info_state_account.key.as_ref(),
-                    applicant_info_state_account.key.as_ref(),
-                    &[nonce],
-                    ]]
-            )?;
+        let account_info_iter = &mut remaining_accounts.iter();
+        let treasury_account = next_account_info(account_info_iter)?;
 
-            msg!("Workflow State Account Created");
+        verify_treasury(program_id, &config_state_data, treasury_account)?;
+
+        let treasury_lamports_before = treasury_account.lamports();
+
+        invoke(
+            &transfer(owner_account.key, treasury_account.key, amount),
+            &[
+                owner_account.clone(),
+                treasury_account.clone(),
+                system_program_id.clone(),
+            ],
+        )?;
+
+        //Record what actually arrived in the treasury rather than what was asked for
+        let received_amount = verify_received_amount(treasury_lamports_before, treasury_account.lamports(), amount)?;
+
+        let timestamp = Clock::get()?.unix_timestamp as u64 * 1000;
+        workflow_state_data.is_paid = true;
+        workflow_state_data.paid_amount = received_amount;
+        workflow_state_data.paid_mint = Pubkey::default();
+        workflow_state_data.paid_at = timestamp;
+        workflow_state_data.updated_at = timestamp;
+
+        workflow_state_data.serialize(&mut &mut workflow_info_state_account.data.borrow_mut()[..])?;
+
+        WorkflowEvent::PaymentRecorded {
+            keys: WorkflowKeys::of(workflow_info_state_account.key, &workflow_state_data),
+            is_paid: true,
+            amount: received_amount,
+            mint: Pubkey::default(),
+            actor: *owner_account.key,
+            timestamp,
         }
+        .emit()?;
 
-        
-        let mut workflow_state_data =
-        try_from_slice_unchecked::<WorkflowState>(&workflow_info_state_account.data.borrow()).unwrap();
-        
-         if workflow_info_state_account.owner != program_id {
-            msg!("Workflow info state account is not owned by the program");
-            return Err(ProgramError::IncorrectProgramId);
+        msg!("Workflow State Account paid with {} lamports", received_amount);
+
+        Ok(())
+    }
+
+    pub fn pay_for_application_with_token(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        amount: u64,
+        bumps: SiblingBumps,
+    ) -> ProgramResult {
+        msg!("Paying For Application With Token");
+        if amount == 0 {
+            msg!("Payment amount must be greater than zero");
+            return Err(WorkflowError::InvalidPaymentAmount.into());
         }
 
-        if owner_account.is_signer == false {
-            msg!("Owner account is not a signer");
-            return Err(ProgramError::MissingRequiredSignature);
+        let WorkflowAccounts {
+            owner_account,
+            workflow_info_state_account,
+            remaining_accounts,
+            config_state_data,
+            mut workflow_state_data,
+            ..
+        } = WorkflowAccounts::load(program_id, accounts, ValidationMode::CompanyPayer, &bumps)?;
+
+        let account_info_iter = &mut remaining_accounts.iter();
+        let source_token_account = next_account_info(account_info_iter)?;
+        let treasury_token_account = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+        let token_program_id = next_account_info(account_info_iter)?;
+
+        if *token_program_id.key != spl_token::id() {
+            msg!("Invalid token program");
+            return Err(WorkflowError::InvalidTokenProgram.into());
         }
 
-        workflow_state_data.is_initialized = true;
-        workflow_state_data.archived = false;
-        workflow_state_data.status = status;
-        workflow_state_data.company_owner_pubkey = company_info_state_data.user_info_state_account_pubkey.clone();
-        workflow_state_data.company_pubkey = *company_info_state_account.key;
-        workflow_state_data.user_pubkey = applicant_info_state_account.key.clone();
-        workflow_state_data.job_pubkey = jobpost_info_state_account.key.clone();
-        workflow_state_data.job_applied_at = Clock::get()?.unix_timestamp as u64 * 1000;
+        //State: Verify the mint against the allow-list of the config
+        let payment_mint = match config_state_data.payment_mint(mint_account.key) {
+            Some(payment_mint) => payment_mint,
+            None => {
+                msg!("Mint {} is not accepted for payments", mint_account.key);
+                return Err(WorkflowError::PaymentMintNotAllowed.into());
+            }
+        };
 
-        //check for subscription plan from the company info state account
-        let mut subscription_status = false;
-        let mut subscription_purchased_at = 0;
-        msg!("Subscription Plan: {}", company_info_state_data.subscription_plan);
-        if company_info_state_data.subscription_plan != "paynuse"{
-            let mut timestamp = Clock::get()?.unix_timestamp;
-            timestamp = timestamp * 1000;
-            msg!("Timestamp: {}", timestamp);
-            msg!("Subscription Valid till: {}", company_info_state_data.subscription_valid_till);
-            subscription_status = if timestamp < company_info_state_data.subscription_valid_till.try_into().unwrap() {
-                true
-            } else {
-                false
-            };
-            
+        if amount != payment_mint.application_price {
+            msg!("Application price is {} tokens, got {}", payment_mint.application_price, amount);
+            return Err(WorkflowError::PaymentAmountMismatch.into());
         }
 
-        if subscription_status {
-            subscription_purchased_at = company_info_state_data.subscription_purchased_on
+        if *mint_account.owner != spl_token::id() {
+            msg!("Mint account is not owned by the token program");
+            return Err(WorkflowError::InvalidTokenAccountData.into());
         }
 
-        workflow_state_data.is_paid = subscription_status;
-        workflow_state_data.paid_amount = 0;
-        workflow_state_data.paid_at = subscription_purchased_at;
-        workflow_state_data.updated_at = Clock::get()?.unix_timestamp as u64 * 1000;
+        let mint_data = Mint::unpack(&mint_account.data.borrow())
+            .map_err(|_| WorkflowError::InvalidTokenAccountData)?;
+        //End: Verify the mint against the allow-list of the config
+
+        //State: Verify Treasury token account
+        let treasury_pda = treasury_pda(program_id, &config_state_data)?;
+
+        if *treasury_token_account.owner != spl_token::id() {
+            msg!("Treasury token account is not owned by the token program");
+            return Err(WorkflowError::InvalidTreasuryTokenAccount.into());
+        }
+
+        let treasury_token_data = TokenAccount::unpack(&treasury_token_account.data.borrow())
+            .map_err(|_| WorkflowError::InvalidTokenAccountData)?;
+
+        if treasury_token_data.owner != treasury_pda || treasury_token_data.mint != *mint_account.key {
+            msg!("Treasury token account must hold the payment mint for the treasury PDA");
+            return Err(WorkflowError::InvalidTreasuryTokenAccount.into());
+        }
+        //End: Verify Treasury token account
+
+        invoke(
+            &transfer_checked(
+                token_program_id.key,
+                source_token_account.key,
+                mint_account.key,
+                treasury_token_account.key,
+                owner_account.key,
+                &[],
+                amount,
+                mint_data.decimals,
+            )?,
+            &[
+                source_token_account.clone(),
+                mint_account.clone(),
+                treasury_token_account.clone(),
+                owner_account.clone(),
+                token_program_id.clone(),
+            ],
+        )?;
+
+        //Record what actually arrived in the treasury rather than what was asked for
+        let treasury_token_amount = TokenAccount::unpack(&treasury_token_account.data.borrow())
+            .map_err(|_| WorkflowError::InvalidTokenAccountData)?
+            .amount;
+        let received_amount = verify_received_amount(treasury_token_data.amount, treasury_token_amount, amount)?;
+
+        let timestamp = Clock::get()?.unix_timestamp as u64 * 1000;
+        workflow_state_data.is_paid = true;
+        workflow_state_data.paid_amount = received_amount;
+        workflow_state_data.paid_mint = *mint_account.key;
+        workflow_state_data.paid_at = timestamp;
+        workflow_state_data.updated_at = timestamp;
+
         workflow_state_data.serialize(&mut &mut workflow_info_state_account.data.borrow_mut()[..])?;
 
-        msg!("Workflow State Account data added");
+        WorkflowEvent::PaymentRecorded {
+            keys: WorkflowKeys::of(workflow_info_state_account.key, &workflow_state_data),
+            is_paid: true,
+            amount: received_amount,
+            mint: *mint_account.key,
+            actor: *owner_account.key,
+            timestamp,
+        }
+        .emit()?;
+
+        msg!("Workflow State Account paid with {} tokens of {}", received_amount, mint_account.key);
 
         Ok(())
     }
 
-    pub fn update_workflow_state(
+    pub fn initialize_config(
         accounts: &[AccountInfo],
         program_id: &Pubkey,
-        archived: bool, //1 true when job is in 'accepted' or 'rejected' or 'withdraw' status
-        is_saved: bool, //1 true when user saves the job
-        status: String, //16 => 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+        payment_authority: Pubkey,
+        user_info_program_id: Pubkey,
+        company_info_program_id: Pubkey,
+        jobpost_info_program_id: Pubkey,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
-        msg!("Updating Workflow Info");
-        let owner_account = next_account_info(account_info_iter)?;
-        let company_info_state_account = next_account_info(account_info_iter)?;
-        let applicant_info_state_account = next_account_info(account_info_iter)?;
-        let jobpost_info_state_account = next_account_info(account_info_iter)?;
-        let workflow_info_state_account = next_account_info(account_info_iter)?;
+        msg!("Initializing Config");
+        let initializer_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
+        let system_program_id = next_account_info(account_info_iter)?;
+        let program_data_account = next_account_info(account_info_iter)?;
+        let treasury_account = next_account_info(account_info_iter)?;
 
-        let user_info_program_id = next_account_info(account_info_iter)?;
-        let _company_info_program_id: &AccountInfo = next_account_info(account_info_iter)?;
-        let jobpost_info_program_id: &AccountInfo = next_account_info(account_info_iter)?;
-        let _system_program_id = next_account_info(account_info_iter)?;
-        
-        //State: Verify Applicant state account
-        // let applicant_info_pda_prefix = APPLICANT_STATE_ACCOUNT_PREFIX;
-
-        // let applicant_pda_seed = &[
-        //     applicant_info_pda_prefix.as_bytes(),
-        //     owner_account.key.as_ref()
-        // ];
-        // let (applicant_info_pda, _nonce) =
-        //     Pubkey::find_program_address(applicant_pda_seed, user_info_program_id.key);
-
-        // if applicant_info_pda != *applicant_info_state_account.key{
-        //     msg!("Invalid Applicant Info State Account");
-        //     return Err(ProgramError::InvalidAccountData);
-        // }
-
-        //End: Verify Applicant state account
+        if !initializer_account.is_signer {
+            msg!("Initializer account is not a signer");
+            return Err(WorkflowError::OwnerNotSigner.into());
+        }
 
-        //State: Verify Company Info state account
-        let company_info_state_data = 
-        try_from_slice_unchecked::<CompanyInfoState>(&company_info_state_account.data.borrow()).unwrap();
+        verify_upgrade_authority(program_id, program_data_account, initializer_account)?;
+
+        let config_state_pda_seed = &[CONFIG_STATE_ACCOUNT_PREFIX.as_bytes()];
 
+        let (config_state_pda, nonce) =
+            Pubkey::find_program_address(config_state_pda_seed, program_id);
 
-        // let company_info_pda_prefix = COMPANY_STATE_ACCOUNT_PREFIX;
+        if config_state_pda != *config_state_account.key {
+            msg!("Invalid Config State PDA");
+            return Err(WorkflowError::ConfigPdaMismatch.into());
+        }
 
-        // let company_info_pda_seed = &[
-        //     company_info_pda_prefix.as_bytes(),
-        //     company_info_state_data.company_seq_number.as_bytes(),
-        //     applicant_info_state_account.key.as_ref()
-        // ];
+        if config_state_account.owner == program_id {
+            msg!("Config state account is already initialized");
+            return Err(WorkflowError::ConfigAlreadyInitialized.into());
+        }
 
-        // let (company_info_pda, _nonce) =
-        //     Pubkey::find_program_address(company_info_pda_seed, company_info_program_id.key);
+        msg!("Creating Config State Account");
+        Self::create_pda_account(
+            initializer_account,
+            config_state_account,
+            system_program_id,
+            program_id,
+            ConfigState::LEN,
+            &[CONFIG_STATE_ACCOUNT_PREFIX.as_bytes(), &[nonce]],
+        )?;
+
+        //Bumps are searched once here and reused by every other instruction
+        let (treasury_pda, treasury_nonce) =
+            Pubkey::find_program_address(&[TREASURY_ACCOUNT_PREFIX.as_bytes()], program_id);
+
+        if treasury_pda != *treasury_account.key {
+            msg!("Invalid Treasury PDA");
+            return Err(WorkflowError::TreasuryPdaMismatch.into());
+        }
 
-        // if company_info_pda != *company_info_state_account.key{
-        //     msg!("Invalid Company Info State Account");
-        //     return Err(ProgramError::InvalidAccountData);
-        // }
+        //A first payment into an empty treasury below the rent exempt minimum would fail
+        let treasury_rent = Rent::get()?.minimum_balance(0);
+        let treasury_lamports = treasury_account.lamports();
 
-        if !company_info_state_data.is_initialized() {
-            msg!("Company info state account is not initialized");
-            return Err(ProgramError::UninitializedAccount);
+        if treasury_lamports < treasury_rent {
+            invoke(
+                &transfer(initializer_account.key, treasury_account.key, treasury_rent - treasury_lamports),
+                &[
+                    initializer_account.clone(),
+                    treasury_account.clone(),
+                    system_program_id.clone(),
+                ],
+            )?;
         }
 
-        // if company_info_state_data.user_info_state_account_pubkey != *applicant_info_state_account.key{
-        //     msg!("company_info_state_account does not match the user_info_state_account_pubkey of the company_info_state_account");
-        //     return Err(ProgramError::InvalidAccountData);
-        // }
+        let config_state_data = ConfigState {
+            is_initialized: true,
+            authority: *initializer_account.key,
+            pending_authority: Pubkey::default(),
+            payment_authority,
+            pending_payment_authority: Pubkey::default(),
+            application_price: 0,
+            payment_mints: Vec::new(),
+            subscription_prices: SubscriptionPrices::default(),
+            user_info_program_id,
+            company_info_program_id,
+            jobpost_info_program_id,
+            allow_saving_archived_jobs: false,
+            bump: nonce,
+            treasury_bump: treasury_nonce,
+        };
+        config_state_data.serialize(&mut &mut config_state_account.data.borrow_mut()[..])?;
+
+        msg!("Config State Account initialized");
 
-        //End: Verify Company Info state account
+        Ok(())
+    }
 
-        //State: Verify Jobpost Info state account
-        let jobpost_info_state_data =
-        try_from_slice_unchecked::<JobPostState>(&jobpost_info_state_account.data.borrow()).unwrap();
-        
-        let jobpost_info_pda_prefix = JOBPOST_STATE_ACCOUNT_PREFIX;
+    pub fn set_authority(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        authority_type: AuthorityType,
+        new_authority: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-        let jobpost_info_pda = &[
-            jobpost_info_pda_prefix.as_bytes(),
-            jobpost_info_state_data.job_number.as_bytes(),
-            company_info_state_account.key.as_ref(),
-        ];
+        msg!("Setting Config Authority");
+        let authority_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
 
-        let (jobpost_info_pda, _nonce) =
-            Pubkey::find_program_address(jobpost_info_pda, jobpost_info_program_id.key);
+        let mut config_state_data = load_config_as_admin(program_id, authority_account, config_state_account)?;
 
-        if jobpost_info_pda != *jobpost_info_state_account.key{
-            msg!("JobPost Info State Account does not match the derived PDA");
-            return Err(ProgramError::InvalidSeeds);
+        match authority_type {
+            AuthorityType::Admin => {
+                msg!("Admin authority transfer proposed to {}", new_authority);
+                config_state_data.pending_authority = new_authority;
+            }
+            AuthorityType::Payment => {
+                msg!("Payment authority transfer proposed to {}", new_authority);
+                config_state_data.pending_payment_authority = new_authority;
+            }
         }
-        //Send: Verify Jobpost Info state account
 
-        if owner_account.is_signer == false {
-            msg!("Owner account is not a signer");
-            return Err(ProgramError::MissingRequiredSignature);
-        }
+        config_state_data.serialize(&mut &mut config_state_account.data.borrow_mut()[..])?;
 
-        let wokrflow_state_pda_prefix = WORKFLOW_STATE_ACCOUNT_PREFIX;
+        Ok(())
+    }
 
-        let wokrflow_state_pda_seed = &[
-            wokrflow_state_pda_prefix.as_bytes(),
-            jobpost_info_state_account.key.as_ref(),
-            applicant_info_state_account.key.as_ref(),
-        ];
+    pub fn accept_authority(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        authority_type: AuthorityType,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-        let (wokrflow_state_pda, _nonce) =
-            Pubkey::find_program_address(wokrflow_state_pda_seed, program_id);
+        msg!("Accepting Config Authority");
+        let pending_authority_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
 
-        if wokrflow_state_pda != *workflow_info_state_account.key{
-            msg!("Invalid Workflow State PDA");
-            return Err(ProgramError::InvalidSeeds);
-        }
+        let mut config_state_data = load_config_state(program_id, config_state_account)?;
 
-        let mut workflow_state_data =
-        try_from_slice_unchecked::<WorkflowState>(&workflow_info_state_account.data.borrow()).unwrap();
+        let (authority, pending_authority) = match authority_type {
+            AuthorityType::Admin => (&mut config_state_data.authority, &mut config_state_data.pending_authority),
+            AuthorityType::Payment => (&mut config_state_data.payment_authority, &mut config_state_data.pending_payment_authority),
+        };
 
-        if !workflow_state_data.is_initialized() {
-            msg!("Workflow state account is not initialized");
-            return Err(ProgramError::UninitializedAccount);
+        if *pending_authority == Pubkey::default()
+            || !pending_authority_account.is_signer
+            || pending_authority_account.key != pending_authority
+        {
+            msg!("Signer is not the pending config authority");
+            return Err(WorkflowError::PendingAuthorityMismatch.into());
         }
 
-        if workflow_info_state_account.owner != program_id {
-            return Err(ProgramError::IncorrectProgramId);
-        }
+        *authority = *pending_authority;
+        *pending_authority = Pubkey::default();
+        msg!("Config authority transferred to {}", authority);
 
-        //applicant_info_state_account => gets created for the applicant or for the company as well
-        if workflow_state_data.company_owner_pubkey != *applicant_info_state_account.key && workflow_state_data.user_pubkey != *applicant_info_state_account.key {
-            msg!("Workflow state account does not belong to the owner");
-            return Err(ProgramError::InvalidAccountData);
-        }
+        config_state_data.serialize(&mut &mut config_state_account.data.borrow_mut()[..])?;
 
-        if workflow_state_data.job_pubkey != *jobpost_info_state_account.key {
-            msg!("Workflow state account does not belong to the jobpost_info_state_account");
-            return Err(ProgramError::InvalidAccountData);
+        Ok(())
+    }
+
+    pub fn set_payment_mints(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        mints: Vec<PaymentMint>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        msg!("Setting Payment Mints");
+        let authority_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
+
+        let mut config_state_data = load_config_as_admin(program_id, authority_account, config_state_account)?;
+
+        if mints.len() > ConfigState::MAX_PAYMENT_MINTS {
+            msg!("At most {} payment mints are supported", ConfigState::MAX_PAYMENT_MINTS);
+            return Err(WorkflowError::TooManyPaymentMints.into());
         }
 
-        workflow_state_data.archived = archived;
-        workflow_state_data.is_saved = is_saved;
-        workflow_state_data.status = status;
-        workflow_state_data.updated_at = Clock::get()?.unix_timestamp as u64 * 1000;        
+        config_state_data.payment_mints = mints;
+        config_state_data.serialize(&mut &mut config_state_account.data.borrow_mut()[..])?;
 
-        workflow_state_data.serialize(&mut &mut workflow_info_state_account.data.borrow_mut()[..])?;
+        msg!("Payment mints updated");
 
-        msg!("Workflow State Account data updated");
+        Ok(())
+    }
+
+    pub fn set_subscription_prices(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        prices: SubscriptionPrices,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        msg!("Setting Subscription Prices");
+        let authority_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
+
+        let mut config_state_data = load_config_as_admin(program_id, authority_account, config_state_account)?;
+
+        config_state_data.subscription_prices = prices;
+        config_state_data.serialize(&mut &mut config_state_account.data.borrow_mut()[..])?;
+
+        msg!("Subscription prices updated");
 
         Ok(())
     }
 
-    pub fn update_workflow_payment_state(
+    pub fn set_application_price(
         accounts: &[AccountInfo],
         program_id: &Pubkey,
-        is_paid: bool,
-        paid_amount: u64
-    ) -> ProgramResult{
+        price: u64,
+    ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
-        msg!("Updating Workflow Payment Info");
+        msg!("Setting Application Price");
+        let authority_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
+
+        let mut config_state_data = load_config_as_admin(program_id, authority_account, config_state_account)?;
+
+        config_state_data.application_price = price;
+        config_state_data.serialize(&mut &mut config_state_account.data.borrow_mut()[..])?;
+
+        msg!("Application price set to {} lamports", price);
+
+        Ok(())
+    }
+
+    pub fn purchase_subscription(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        plan: SubscriptionPlan,
+        company_bump: u8,
+        signer_applicant_bump: u8,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        msg!("Purchasing Subscription");
         let owner_account = next_account_info(account_info_iter)?;
-        let _logged_in_user_pubkey = next_account_info(account_info_iter)?;
         let company_info_state_account = next_account_info(account_info_iter)?;
-        let applicant_info_state_account = next_account_info(account_info_iter)?;
-        let jobpost_info_state_account = next_account_info(account_info_iter)?;
-        let workflow_info_state_account = next_account_info(account_info_iter)?;
+        let subscription_state_account = next_account_info(account_info_iter)?;
+        let treasury_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
 
         let user_info_program_id = next_account_info(account_info_iter)?;
-        let _company_info_program_id: &AccountInfo = next_account_info(account_info_iter)?;
-        let jobpost_info_program_id: &AccountInfo = next_account_info(account_info_iter)?;
-        let _system_program_id = next_account_info(account_info_iter)?;
-        
-        //State: Verify Applicant state account
-        let applicant_info_pda_prefix = APPLICANT_STATE_ACCOUNT_PREFIX;
-
-        let applicant_info_state_data = 
-        try_from_slice_unchecked::<ApplicantInfoState>(&applicant_info_state_account.data.borrow()).unwrap();
+        let system_program_id = next_account_info(account_info_iter)?;
 
-        if !applicant_info_state_data.is_initialized(){
-            msg!("Applicant Info State Account is not initialized");
-            return Err(ProgramError::UninitializedAccount);
+        if !owner_account.is_signer {
+            msg!("Owner account is not a signer");
+            return Err(WorkflowError::OwnerNotSigner.into());
         }
 
-        let applicant_pda_seed = &[
-            applicant_info_pda_prefix.as_bytes(),
-            applicant_info_state_data.owner_pubkey.as_ref()
-        ];
-        let (applicant_info_pda, _nonce) =
-            Pubkey::find_program_address(applicant_pda_seed, user_info_program_id.key);
+        let config_state_data = load_config_state(program_id, config_state_account)?;
 
-        if applicant_info_pda != *applicant_info_state_account.key{
-            msg!("Invalid Applicant Info State Account");
-            return Err(ProgramError::InvalidAccountData);
+        if *user_info_program_id.key != config_state_data.user_info_program_id {
+            msg!("User info program does not match the config");
+            return Err(WorkflowError::SiblingProgramMismatch.into());
         }
 
-        //End: Verify Applicant state account
+        let price = config_state_data.subscription_prices.price_of(plan);
+        if price == 0 {
+            msg!("Subscription plan {:?} is not for sale", plan);
+            return Err(WorkflowError::SubscriptionPlanNotForSale.into());
+        }
 
         //State: Verify Company Info state account
-        let company_info_state_data = 
-        try_from_slice_unchecked::<CompanyInfoState>(&company_info_state_account.data.borrow()).unwrap();
+        let company_info_state_data =
+            load_company(&config_state_data, company_info_state_account, company_bump)?;
+
+        let signer_applicant_pda = Pubkey::create_program_address(
+            &[
+                APPLICANT_STATE_ACCOUNT_PREFIX.as_bytes(),
+                owner_account.key.as_ref(),
+                &[signer_applicant_bump],
+            ],
+            user_info_program_id.key,
+        )
+        .ok();
+
+        if signer_applicant_pda != Some(company_info_state_data.user_info_state_account_pubkey) {
+            msg!("Only the company owner can purchase a subscription");
+            return Err(WorkflowError::UnauthorizedSubscriptionPurchaser.into());
+        }
+        //End: Verify Company Info state account
 
+        verify_treasury(program_id, &config_state_data, treasury_account)?;
 
-        // let company_info_pda_prefix = COMPANY_STATE_ACCOUNT_PREFIX;
+        let subscription_state_pda_seed = &[
+            SUBSCRIPTION_STATE_ACCOUNT_PREFIX.as_bytes(),
+            company_info_state_account.key.as_ref(),
+        ];
 
-        // let company_info_pda_seed = &[
-        //     company_info_pda_prefix.as_bytes(),
-        //     company_info_state_data.company_seq_number.as_bytes(),
-        //     applicant_info_state_account.key.as_ref()
-        // ];
+        //An existing subscription is verified with its stored bump by load_subscription_state
+        let nonce = if subscription_state_account.owner == program_id {
+            let subscription_state_data = load_subscription_state(
+                program_id,
+                company_info_state_account,
+                subscription_state_account,
+            )?
+            .ok_or(WorkflowError::InvalidSubscriptionData)?;
+
+            subscription_state_data.bump
+        } else {
+            let (subscription_state_pda, nonce) =
+                Pubkey::find_program_address(subscription_state_pda_seed, program_id);
+
+            if subscription_state_pda != *subscription_state_account.key {
+                msg!("Invalid Subscription State PDA");
+                return Err(WorkflowError::SubscriptionPdaMismatch.into());
+            }
 
-        // let (company_info_pda, _nonce) =
-        //     Pubkey::find_program_address(company_info_pda_seed, company_info_program_id.key);
+            nonce
+        };
+
+        if subscription_state_account.owner != program_id {
+            msg!("Creating Subscription State Account");
+            Self::create_pda_account(
+                owner_account,
+                subscription_state_account,
+                system_program_id,
+                program_id,
+                SubscriptionState::LEN,
+                &[
+                    SUBSCRIPTION_STATE_ACCOUNT_PREFIX.as_bytes(),
+                    company_info_state_account.key.as_ref(),
+                    &[nonce],
+                ],
+            )?;
+        }
 
-        // if company_info_pda != *company_info_state_account.key{
-        //     msg!("Invalid Company Info State Account");
-        //     return Err(ProgramError::InvalidAccountData);
-        // }
+        let mut subscription_state_data =
+        try_from_slice_unchecked::<SubscriptionState>(&subscription_state_account.data.borrow())
+            .map_err(|_| WorkflowError::InvalidSubscriptionData)?;
+
+        //A renewal of an active subscription extends it instead of dropping the remaining time,
+        //and keeps the longer of both plans
+        let timestamp = Clock::get()?.unix_timestamp as u64 * 1000;
+        let (valid_from, renewed_plan) = if subscription_state_data.is_active(timestamp) {
+            if subscription_state_data.plan == SubscriptionPlan::Forever {
+                msg!("Company already has a forever subscription");
+                return Err(WorkflowError::SubscriptionNeverExpires.into());
+            }
 
-        if !company_info_state_data.is_initialized() {
-            msg!("Company info state account is not initialized");
-            return Err(ProgramError::UninitializedAccount);
+            (subscription_state_data.valid_till, plan.max(subscription_state_data.plan))
+        } else {
+            (timestamp, plan)
+        };
+
+        invoke(
+            &transfer(owner_account.key, treasury_account.key, price),
+            &[
+                owner_account.clone(),
+                treasury_account.clone(),
+                system_program_id.clone(),
+            ],
+        )?;
+
+        let valid_till = match plan.duration_in_millis() {
+            Some(duration) => valid_from.saturating_add(duration),
+            None => u64::MAX,
+        };
+
+        subscription_state_data.is_initialized = true;
+        subscription_state_data.company_pubkey = *company_info_state_account.key;
+        subscription_state_data.plan = renewed_plan;
+        subscription_state_data.purchased_on = timestamp;
+        subscription_state_data.valid_till = valid_till;
+        subscription_state_data.paid_amount = price;
+        subscription_state_data.bump = nonce;
+
+        subscription_state_data.serialize(&mut &mut subscription_state_account.data.borrow_mut()[..])?;
+
+        WorkflowEvent::SubscriptionPurchased {
+            company_pubkey: *company_info_state_account.key,
+            plan: renewed_plan,
+            amount: price,
+            valid_till,
         }
+        .emit()?;
 
-        // if company_info_state_data.user_info_state_account_pubkey != *applicant_info_state_account.key{
-        //     msg!("company_info_state_account does not match the user_info_state_account_pubkey of the company_info_state_account");
-        //     return Err(ProgramError::InvalidAccountData);
-        // }
+        msg!("Subscription {:?} valid till {}", renewed_plan, valid_till);
 
-        //End: Verify Company Info state account
+        Ok(())
+    }
 
-        //State: Verify Jobpost Info state account
-        let jobpost_info_state_data =
-        try_from_slice_unchecked::<JobPostState>(&jobpost_info_state_account.data.borrow()).unwrap();
-        
-        let jobpost_info_pda_prefix = JOBPOST_STATE_ACCOUNT_PREFIX;
+    pub fn import_legacy_subscription(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        company_bump: u8,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-        let jobpost_info_pda = &[
-            jobpost_info_pda_prefix.as_bytes(),
-            jobpost_info_state_data.job_number.as_bytes(),
-            company_info_state_account.key.as_ref(),
-        ];
+        msg!("Importing Legacy Subscription");
+        let authority_account = next_account_info(account_info_iter)?;
+        let company_info_state_account = next_account_info(account_info_iter)?;
+        let subscription_state_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
+        let system_program_id = next_account_info(account_info_iter)?;
+
+        let config_state_data = load_config_as_admin(program_id, authority_account, config_state_account)?;
 
-        let (jobpost_info_pda, _nonce) =
-            Pubkey::find_program_address(jobpost_info_pda, jobpost_info_program_id.key);
+        let company_info_state_data =
+            load_company(&config_state_data, company_info_state_account, company_bump)?;
 
-        if jobpost_info_pda != *jobpost_info_state_account.key{
-            msg!("JobPost Info State Account does not match the derived PDA");
-            return Err(ProgramError::InvalidSeeds);
+        let plan = SubscriptionPlan::from_str(&company_info_state_data.subscription_plan)?;
+        let timestamp = Clock::get()?.unix_timestamp as u64 * 1000;
+
+        if plan == SubscriptionPlan::PayNUse || company_info_state_data.subscription_valid_till <= timestamp {
+            msg!(
+                "Legacy subscription '{}' valid till {} is not active",
+                company_info_state_data.subscription_plan,
+                company_info_state_data.subscription_valid_till
+            );
+            return Err(WorkflowError::LegacySubscriptionNotActive.into());
         }
-        //Send: Verify Jobpost Info state account
 
-        if owner_account.is_signer == false {
-            msg!("Owner account is not a signer");
-            return Err(ProgramError::MissingRequiredSignature);
+        //Purchases made through this program are never overwritten by an import
+        if subscription_state_account.owner == program_id {
+            msg!("Company already has a subscription state account");
+            return Err(WorkflowError::SubscriptionAlreadyExists.into());
         }
 
-        let wokrflow_state_pda_prefix = WORKFLOW_STATE_ACCOUNT_PREFIX;
+        let (subscription_state_pda, nonce) = Pubkey::find_program_address(
+            &[
+                SUBSCRIPTION_STATE_ACCOUNT_PREFIX.as_bytes(),
+                company_info_state_account.key.as_ref(),
+            ],
+            program_id,
+        );
+
+        if subscription_state_pda != *subscription_state_account.key {
+            msg!("Invalid Subscription State PDA");
+            return Err(WorkflowError::SubscriptionPdaMismatch.into());
+        }
 
-        let wokrflow_state_pda_seed = &[
-            wokrflow_state_pda_prefix.as_bytes(),
-            jobpost_info_state_account.key.as_ref(),
-            applicant_info_state_account.key.as_ref(),
-        ];
+        Self::create_pda_account(
+            authority_account,
+            subscription_state_account,
+            system_program_id,
+            program_id,
+            SubscriptionState::LEN,
+            &[
+                SUBSCRIPTION_STATE_ACCOUNT_PREFIX.as_bytes(),
+                company_info_state_account.key.as_ref(),
+                &[nonce],
+            ],
+        )?;
+
+        let valid_till = match plan {
+            SubscriptionPlan::Forever => u64::MAX,
+            _ => company_info_state_data.subscription_valid_till,
+        };
+
+        let subscription_state_data = SubscriptionState {
+            is_initialized: true,
+            company_pubkey: *company_info_state_account.key,
+            plan,
+            purchased_on: company_info_state_data.subscription_purchased_on,
+            valid_till,
+            paid_amount: 0,
+            bump: nonce,
+        };
+        subscription_state_data.serialize(&mut &mut subscription_state_account.data.borrow_mut()[..])?;
+
+        WorkflowEvent::SubscriptionPurchased {
+            company_pubkey: *company_info_state_account.key,
+            plan,
+            amount: 0,
+            valid_till,
+        }
+        .emit()?;
+
+        msg!("Legacy subscription {:?} imported, valid till {}", plan, valid_till);
+
+        Ok(())
+    }
+
+    pub fn withdraw_treasury(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        msg!("Withdrawing Treasury");
+        let authority_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
+        let treasury_account = next_account_info(account_info_iter)?;
+        let recipient_account = next_account_info(account_info_iter)?;
+        let system_program_id = next_account_info(account_info_iter)?;
 
-        let (wokrflow_state_pda, _nonce) =
-            Pubkey::find_program_address(wokrflow_state_pda_seed, program_id);
+        let config_state_data = load_config_as_admin(program_id, authority_account, config_state_account)?;
 
-        if wokrflow_state_pda != *workflow_info_state_account.key{
-            msg!("Invalid Workflow State PDA");
-            return Err(ProgramError::InvalidSeeds);
+        verify_treasury(program_id, &config_state_data, treasury_account)?;
+
+        //The treasury keeps its rent exempt minimum so payments below it can still be received
+        let available_lamports = treasury_account
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+
+        if amount > available_lamports {
+            msg!("Treasury can pay {} lamports, requested {}", available_lamports, amount);
+            return Err(WorkflowError::InsufficientTreasuryBalance.into());
+        }
+
+        invoke_signed(
+            &transfer(treasury_account.key, recipient_account.key, amount),
+            &[
+                treasury_account.clone(),
+                recipient_account.clone(),
+                system_program_id.clone(),
+            ],
+            &[&[TREASURY_ACCOUNT_PREFIX.as_bytes(), &[config_state_data.treasury_bump]]],
+        )?;
+
+        WorkflowEvent::TreasuryWithdrawn {
+            amount,
+            mint: Pubkey::default(),
+            recipient: *recipient_account.key,
+            actor: *authority_account.key,
         }
+        .emit()?;
+
+        msg!("Withdrew {} lamports from the treasury", amount);
+
+        Ok(())
+    }
 
-        let mut workflow_state_data =
-        try_from_slice_unchecked::<WorkflowState>(&workflow_info_state_account.data.borrow()).unwrap();
+    pub fn withdraw_treasury_tokens(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-        if !workflow_state_data.is_initialized() {
-            msg!("Workflow state account is not initialized");
-            return Err(ProgramError::UninitializedAccount);
+        msg!("Withdrawing Treasury Tokens");
+        let authority_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
+        let treasury_account = next_account_info(account_info_iter)?;
+        let treasury_token_account = next_account_info(account_info_iter)?;
+        let recipient_token_account = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+        let token_program_id = next_account_info(account_info_iter)?;
+
+        let config_state_data = load_config_as_admin(program_id, authority_account, config_state_account)?;
+
+        verify_treasury(program_id, &config_state_data, treasury_account)?;
+
+        if *token_program_id.key != spl_token::id() {
+            msg!("Invalid token program");
+            return Err(WorkflowError::InvalidTokenProgram.into());
         }
 
-        if workflow_info_state_account.owner != program_id {
-            return Err(ProgramError::IncorrectProgramId);
+        if *mint_account.owner != spl_token::id() {
+            msg!("Mint account is not owned by the token program");
+            return Err(WorkflowError::InvalidTokenAccountData.into());
         }
 
-        if workflow_state_data.company_owner_pubkey != company_info_state_data.user_info_state_account_pubkey {
-            msg!("Workflow company owner state account does not match with the company owner");
-            return Err(ProgramError::InvalidAccountData);
+        let mint_data = Mint::unpack(&mint_account.data.borrow())
+            .map_err(|_| WorkflowError::InvalidTokenAccountData)?;
+
+        //The token program only lets the treasury PDA move tokens of accounts it holds
+        invoke_signed(
+            &transfer_checked(
+                token_program_id.key,
+                treasury_token_account.key,
+                mint_account.key,
+                recipient_token_account.key,
+                treasury_account.key,
+                &[],
+                amount,
+                mint_data.decimals,
+            )?,
+            &[
+                treasury_token_account.clone(),
+                mint_account.clone(),
+                recipient_token_account.clone(),
+                treasury_account.clone(),
+                token_program_id.clone(),
+            ],
+            &[&[TREASURY_ACCOUNT_PREFIX.as_bytes(), &[config_state_data.treasury_bump]]],
+        )?;
+
+        WorkflowEvent::TreasuryWithdrawn {
+            amount,
+            mint: *mint_account.key,
+            recipient: *recipient_token_account.key,
+            actor: *authority_account.key,
         }
+        .emit()?;
+
+        msg!("Withdrew {} tokens of {} from the treasury", amount, mint_account.key);
+
+        Ok(())
+    }
+
+    pub fn set_sibling_programs(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        user_info_program_id: Pubkey,
+        company_info_program_id: Pubkey,
+        jobpost_info_program_id: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        msg!("Setting Sibling Programs");
+        let authority_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
+
+        let mut config_state_data = load_config_as_admin(program_id, authority_account, config_state_account)?;
+
+        config_state_data.user_info_program_id = user_info_program_id;
+        config_state_data.company_info_program_id = company_info_program_id;
+        config_state_data.jobpost_info_program_id = jobpost_info_program_id;
+        config_state_data.serialize(&mut &mut config_state_account.data.borrow_mut()[..])?;
+
+        msg!("Sibling programs updated");
+
+        Ok(())
+    }
 
-        if workflow_state_data.job_pubkey != *jobpost_info_state_account.key {
-            msg!("Workflow state account does not belong to the jobpost_info_state_account");
-            return Err(ProgramError::InvalidAccountData);
+    pub fn set_allow_saving_archived_jobs(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        allow: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        msg!("Setting Allow Saving Archived Jobs");
+        let authority_account = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
+
+        let mut config_state_data = load_config_as_admin(program_id, authority_account, config_state_account)?;
+
+        config_state_data.allow_saving_archived_jobs = allow;
+        config_state_data.serialize(&mut &mut config_state_account.data.borrow_mut()[..])?;
+
+        msg!("Saving archived jobs allowed: {}", allow);
+
+        Ok(())
+    }
+
+    pub fn migrate_workflow_state(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        msg!("Migrating Workflow State");
+        let payer_account = next_account_info(account_info_iter)?;
+        let workflow_info_state_account = next_account_info(account_info_iter)?;
+        let system_program_id = next_account_info(account_info_iter)?;
+
+        if !payer_account.is_signer {
+            msg!("Payer account is not a signer");
+            return Err(WorkflowError::OwnerNotSigner.into());
         }
 
-        if owner_account.key.to_string() != SUBSCRIPTION_MODIFIER_PUBKEY.to_string() {
-            msg!("Unauthorized subscription modifier trying to update the subscription");
-            return Err(ProgramError::InvalidAccountData);
+        if workflow_info_state_account.owner != program_id {
+            msg!("Workflow info state account is not owned by the program");
+            return Err(WorkflowError::WorkflowNotOwnedByProgram.into());
         }
 
-        workflow_state_data.is_paid = is_paid;
-        workflow_state_data.paid_amount = paid_amount;
-        workflow_state_data.paid_at = Clock::get()?.unix_timestamp as u64 * 1000;
-        workflow_state_data.updated_at = Clock::get()?.unix_timestamp as u64 * 1000;       
+        //Migrating twice is a no-op, so a script can send the whole account list again
+        let layout_version = WorkflowState::layout_version(&workflow_info_state_account.data.borrow());
+
+        let workflow_state_data = match layout_version {
+            Some(WorkflowState::VERSION) => {
+                msg!("Workflow State Account already has layout version {}", WorkflowState::VERSION);
+                return Ok(());
+            }
+            Some(version) => {
+                msg!("Workflow state account has layout version {}, expected {}", version, WorkflowState::VERSION);
+                return Err(WorkflowError::UnsupportedWorkflowVersion.into());
+            }
+            None => {
+                let legacy_state_data =
+                try_from_slice_unchecked::<WorkflowStateV1>(&workflow_info_state_account.data.borrow())
+                    .map_err(|_| WorkflowError::InvalidWorkflowData)?;
+
+                if !legacy_state_data.is_initialized() {
+                    msg!("Workflow state account is not initialized");
+                    return Err(WorkflowError::WorkflowNotInitialized.into());
+                }
+
+                //Only a workflow PDA is rewritten, any other account of the program would not derive to its key
+                let (wokrflow_state_pda, nonce) = Pubkey::find_program_address(
+                    &[
+                        WORKFLOW_STATE_ACCOUNT_PREFIX.as_bytes(),
+                        legacy_state_data.job_pubkey.as_ref(),
+                        legacy_state_data.user_pubkey.as_ref(),
+                    ],
+                    program_id,
+                );
+
+                if wokrflow_state_pda != *workflow_info_state_account.key {
+                    msg!("Invalid Workflow State PDA");
+                    return Err(WorkflowError::WorkflowPdaMismatch.into());
+                }
+
+                legacy_state_data.upgrade(nonce)
+            }
+        };
+
+        let rent_exempt_lamports = Rent::get()?.minimum_balance(WorkflowState::LEN);
+        let workflow_lamports = workflow_info_state_account.lamports();
+
+        if workflow_lamports < rent_exempt_lamports {
+            invoke(
+                &transfer(
+                    payer_account.key,
+                    workflow_info_state_account.key,
+                    rent_exempt_lamports - workflow_lamports,
+                ),
+                &[
+                    payer_account.clone(),
+                    workflow_info_state_account.clone(),
+                    system_program_id.clone(),
+                ],
+            )?;
+        }
 
+        workflow_info_state_account.realloc(WorkflowState::LEN, true)?;
         workflow_state_data.serialize(&mut &mut workflow_info_state_account.data.borrow_mut()[..])?;
 
-        msg!("Workflow State Account payment staus updated");
+        WorkflowEvent::WorkflowMigrated {
+            workflow_pubkey: *workflow_info_state_account.key,
+            from_version: WorkflowStateV1::VERSION,
+            to_version: WorkflowState::VERSION,
+        }
+        .emit()?;
+
+        msg!(
+            "Workflow State Account migrated from layout version {} to {}",
+            WorkflowStateV1::VERSION,
+            WorkflowState::VERSION
+        );
 
         Ok(())
     }
 
+    /// Verifies the subscription PDA of the company with its stored bump,
+    /// `None` when the account is not owned by the program, no subscription was ever purchased then.
+    /// Creates a PDA of the program paid by `payer`. Lamports sent to the address beforehand make
+    /// create_account fail, so a funded address is topped up to rent exemption, allocated and assigned instead.
+    fn create_pda_account<'a>(
+        payer_account: &AccountInfo<'a>,
+        new_account: &AccountInfo<'a>,
+        system_program_id: &AccountInfo<'a>,
+        program_id: &Pubkey,
+        space: usize,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let rent_lamports = Rent::default().minimum_balance(space);
+
+        if new_account.lamports() == 0 {
+            return invoke_signed(
+                &create_account(payer_account.key, new_account.key, rent_lamports, space as u64, program_id),
+                &[payer_account.clone(), new_account.clone(), system_program_id.clone()],
+                &[signer_seeds],
+            );
+        }
+
+        let top_up = rent_lamports.saturating_sub(new_account.lamports());
+        if top_up > 0 {
+            invoke(
+                &transfer(payer_account.key, new_account.key, top_up),
+                &[payer_account.clone(), new_account.clone(), system_program_id.clone()],
+            )?;
+        }
+
+        invoke_signed(
+            &allocate(new_account.key, space as u64),
+            &[new_account.clone(), system_program_id.clone()],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &assign(new_account.key, program_id),
+            &[new_account.clone(), system_program_id.clone()],
+            &[signer_seeds],
+        )
+    }
 }
@@ -1,25 +1,51 @@
+use std::convert::TryFrom;
+
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     program_pack::{IsInitialized, Sealed},
     pubkey::Pubkey,
 };
 
-#[derive(Debug, PartialEq, Clone)]
+use std::str::FromStr;
+
+use crate::{error::WorkflowError, workflow_status::WorkflowStatus};
+
+/// Status change of a workflow, `actor` is the wallet that signed it.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct WorkflowHistoryEntry {
+    pub status: WorkflowStatus, //1
+    pub actor: Pubkey, //32
+    pub timestamp: u64, //8 => timestamp in unix format
+}
+
+impl WorkflowHistoryEntry {
+    pub const LEN: usize = 1+32+8; //41
+}
+
+/// Every field up to the history has a fixed size, so the Borsh encoding puts each field at the
+/// offset given by the `*_OFFSET` constants and indexers can `memcmp` filter on them.
+#[derive(Debug, PartialEq, Clone, Default)]
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct WorkflowState {
+    pub discriminator: [u8; 8], //8 => WorkflowState::DISCRIMINATOR
+    pub version: u8, //1 => layout version, 1 is the legacy String status layout without discriminator
     pub is_initialized: bool, //1
     pub archived: bool, //1 true when job is in 'accepted' or 'rejected' or 'withdraw' status
     pub is_saved: bool, //1 true when job is in 'saved' status
+    pub status: WorkflowStatus, //1 => 'saved' or 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+    pub bump: u8, //1 => canonical bump of the workflow PDA, found once on creation
     pub company_owner_pubkey: Pubkey, //32
     pub company_pubkey: Pubkey, //32
     pub user_pubkey: Pubkey, //32
     pub job_pubkey: Pubkey, //32
-    pub status: String, //16 => 'saved' or 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
     pub job_applied_at: u64, //8 => timestamp in unix format
     pub is_paid: bool, //1
     pub paid_amount: u64,//8
     pub paid_at: u64, //8 => timestamp in unix format
     pub updated_at: u64, //8 => timestamp in unix format
+    pub paid_mint: Pubkey, //32 => SPL mint of the payment, default pubkey when paid in SOL
+    pub history: Vec<WorkflowHistoryEntry>, //4+41*8 => last status changes, oldest first
 }
 impl Sealed for WorkflowState {}
 impl IsInitialized for WorkflowState {
@@ -29,8 +55,305 @@ impl IsInitialized for WorkflowState {
 }
 
 impl WorkflowState {
+    /// Leading bytes of every workflow state account, no other account of the program starts with them.
+    pub const DISCRIMINATOR: [u8; 8] = *b"workflow";
+    /// Fixed layout followed by the status history.
+    pub const VERSION: u8 = 2;
+
+    pub const MAX_HISTORY: usize = 8;
+
+    pub const DISCRIMINATOR_OFFSET: usize = 0;
+    pub const VERSION_OFFSET: usize = Self::DISCRIMINATOR_OFFSET + 8;
+    pub const IS_INITIALIZED_OFFSET: usize = Self::VERSION_OFFSET + 1;
+    pub const ARCHIVED_OFFSET: usize = Self::IS_INITIALIZED_OFFSET + 1;
+    pub const IS_SAVED_OFFSET: usize = Self::ARCHIVED_OFFSET + 1;
+    pub const STATUS_OFFSET: usize = Self::IS_SAVED_OFFSET + 1;
+    pub const BUMP_OFFSET: usize = Self::STATUS_OFFSET + 1;
+    pub const COMPANY_OWNER_PUBKEY_OFFSET: usize = Self::BUMP_OFFSET + 1;
+    pub const COMPANY_PUBKEY_OFFSET: usize = Self::COMPANY_OWNER_PUBKEY_OFFSET + 32;
+    pub const USER_PUBKEY_OFFSET: usize = Self::COMPANY_PUBKEY_OFFSET + 32;
+    pub const JOB_PUBKEY_OFFSET: usize = Self::USER_PUBKEY_OFFSET + 32;
+    pub const JOB_APPLIED_AT_OFFSET: usize = Self::JOB_PUBKEY_OFFSET + 32;
+    pub const IS_PAID_OFFSET: usize = Self::JOB_APPLIED_AT_OFFSET + 8;
+    pub const PAID_AMOUNT_OFFSET: usize = Self::IS_PAID_OFFSET + 1;
+    pub const PAID_AT_OFFSET: usize = Self::PAID_AMOUNT_OFFSET + 8;
+    pub const UPDATED_AT_OFFSET: usize = Self::PAID_AT_OFFSET + 8;
+    pub const PAID_MINT_OFFSET: usize = Self::UPDATED_AT_OFFSET + 8;
+
+    pub const HISTORY_OFFSET: usize = Self::PAID_MINT_OFFSET + 32;
+
+    pub const LEN: usize = Self::HISTORY_OFFSET + 4 + WorkflowHistoryEntry::LEN * Self::MAX_HISTORY; //539
+
+    /// Layout version of workflow account data, `None` without the discriminator, as in the legacy layout.
+    pub fn layout_version(data: &[u8]) -> Option<u8> {
+        if data.get(Self::DISCRIMINATOR_OFFSET..Self::DISCRIMINATOR_OFFSET + 8) != Some(&Self::DISCRIMINATOR[..]) {
+            return None;
+        }
+
+        data.get(Self::VERSION_OFFSET).copied()
+    }
+
+    /// Appends a status change, dropping the oldest one once `MAX_HISTORY` changes are kept.
+    pub fn record_status(&mut self, status: WorkflowStatus, actor: Pubkey, timestamp: u64) {
+        if self.history.len() >= Self::MAX_HISTORY {
+            self.history.remove(0);
+        }
+
+        self.history.push(WorkflowHistoryEntry {
+            status,
+            actor,
+            timestamp,
+        });
+    }
+}
+
+/// Read only view over the data of a workflow state account,
+/// fields are read in place at their offsets without decoding the account.
+#[derive(Clone, Copy)]
+pub struct WorkflowStateView<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> WorkflowStateView<'a> {
+    /// `None` when the data is too short for the current layout.
+    pub fn new(data: &'a [u8]) -> Option<Self> {
+        if data.len() < WorkflowState::LEN {
+            return None;
+        }
+
+        Some(WorkflowStateView { data })
+    }
+
+    fn read_u64(&self, offset: usize) -> u64 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&self.data[offset..offset + 8]);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn read_pubkey(&self, offset: usize) -> Pubkey {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&self.data[offset..offset + 32]);
+        Pubkey::new_from_array(bytes)
+    }
+
+    pub fn discriminator(&self) -> &'a [u8] {
+        &self.data[WorkflowState::DISCRIMINATOR_OFFSET..WorkflowState::DISCRIMINATOR_OFFSET + 8]
+    }
+
+    pub fn version(&self) -> u8 {
+        self.data[WorkflowState::VERSION_OFFSET]
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.data[WorkflowState::IS_INITIALIZED_OFFSET] != 0
+    }
+
+    pub fn archived(&self) -> bool {
+        self.data[WorkflowState::ARCHIVED_OFFSET] != 0
+    }
+
+    pub fn is_saved(&self) -> bool {
+        self.data[WorkflowState::IS_SAVED_OFFSET] != 0
+    }
+
+    pub fn status(&self) -> Result<WorkflowStatus, WorkflowError> {
+        WorkflowStatus::try_from(self.data[WorkflowState::STATUS_OFFSET])
+    }
+
+    pub fn bump(&self) -> u8 {
+        self.data[WorkflowState::BUMP_OFFSET]
+    }
+
+    pub fn company_owner_pubkey(&self) -> Pubkey {
+        self.read_pubkey(WorkflowState::COMPANY_OWNER_PUBKEY_OFFSET)
+    }
+
+    pub fn company_pubkey(&self) -> Pubkey {
+        self.read_pubkey(WorkflowState::COMPANY_PUBKEY_OFFSET)
+    }
+
+    pub fn user_pubkey(&self) -> Pubkey {
+        self.read_pubkey(WorkflowState::USER_PUBKEY_OFFSET)
+    }
+
+    pub fn job_pubkey(&self) -> Pubkey {
+        self.read_pubkey(WorkflowState::JOB_PUBKEY_OFFSET)
+    }
+
+    pub fn job_applied_at(&self) -> u64 {
+        self.read_u64(WorkflowState::JOB_APPLIED_AT_OFFSET)
+    }
+
+    pub fn is_paid(&self) -> bool {
+        self.data[WorkflowState::IS_PAID_OFFSET] != 0
+    }
+
+    pub fn paid_amount(&self) -> u64 {
+        self.read_u64(WorkflowState::PAID_AMOUNT_OFFSET)
+    }
+
+    pub fn paid_at(&self) -> u64 {
+        self.read_u64(WorkflowState::PAID_AT_OFFSET)
+    }
+
+    pub fn updated_at(&self) -> u64 {
+        self.read_u64(WorkflowState::UPDATED_AT_OFFSET)
+    }
+
+    pub fn paid_mint(&self) -> Pubkey {
+        self.read_pubkey(WorkflowState::PAID_MINT_OFFSET)
+    }
+
+    pub fn history_len(&self) -> usize {
+        let bytes = &self.data[WorkflowState::HISTORY_OFFSET..WorkflowState::HISTORY_OFFSET + 4];
+        let len = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        len.min(WorkflowState::MAX_HISTORY)
+    }
+
+    /// Status change at `index`, oldest first.
+    pub fn history_entry(&self, index: usize) -> Option<Result<WorkflowHistoryEntry, WorkflowError>> {
+        if index >= self.history_len() {
+            return None;
+        }
+
+        let offset = WorkflowState::HISTORY_OFFSET + 4 + index * WorkflowHistoryEntry::LEN;
+        Some(WorkflowStatus::try_from(self.data[offset]).map(|status| WorkflowHistoryEntry {
+            status,
+            actor: self.read_pubkey(offset + 1),
+            timestamp: self.read_u64(offset + 1 + 32),
+        }))
+    }
+}
+
+/// Layout version 1, created by AddWorkflowState before the fixed layout.
+/// Only read by MigrateWorkflowState to rewrite the account in the current layout.
+#[derive(Debug, PartialEq, Clone)]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct WorkflowStateV1 {
+    pub is_initialized: bool, //1
+    pub archived: bool, //1 true when job is in 'accepted' or 'rejected' or 'withdraw' status
+    pub is_saved: bool, //1 true when job is in 'saved' status
+    pub company_owner_pubkey: Pubkey, //32
+    pub company_pubkey: Pubkey, //32
+    pub user_pubkey: Pubkey, //32
+    pub job_pubkey: Pubkey, //32
+    pub status: String, //16 => 'saved' or 'applied' or 'in_progress' or 'accepted' or 'rejected' or 'withdraw'
+    pub job_applied_at: u64, //8 => timestamp in unix format
+    pub is_paid: bool, //1
+    pub paid_amount: u64,//8
+    pub paid_at: u64, //8 => timestamp in unix format
+    pub updated_at: u64, //8 => timestamp in unix format
+}
+impl IsInitialized for WorkflowStateV1 {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl WorkflowStateV1 {
+    pub const VERSION: u8 = 1;
     pub const LEN: usize = 1+1+1+16+32+32+32+32+8+1+8+8+8; //179 ~200
+
+    /// Current layout of the same workflow, `bump` is the canonical bump of its PDA.
+    /// The legacy status was a free-form string, a status outside the known ones is
+    /// migrated as an archived 'saved' workflow so the candidate can still close it.
+    pub fn upgrade(&self, bump: u8) -> WorkflowState {
+        let (status, archived) = match WorkflowStatus::from_str(&self.status) {
+            Ok(status) => (status, self.archived),
+            Err(_) => (WorkflowStatus::Saved, true),
+        };
+
+        WorkflowState {
+            discriminator: WorkflowState::DISCRIMINATOR,
+            version: WorkflowState::VERSION,
+            is_initialized: self.is_initialized,
+            archived,
+            is_saved: self.is_saved,
+            status,
+            bump,
+            company_owner_pubkey: self.company_owner_pubkey,
+            company_pubkey: self.company_pubkey,
+            user_pubkey: self.user_pubkey,
+            job_pubkey: self.job_pubkey,
+            job_applied_at: self.job_applied_at,
+            is_paid: self.is_paid,
+            paid_amount: self.paid_amount,
+            paid_at: self.paid_at,
+            updated_at: self.updated_at,
+            paid_mint: Pubkey::default(),
+            history: Vec::new(),
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy(status: &str) -> WorkflowStateV1 {
+        WorkflowStateV1 {
+            is_initialized: true,
+            archived: false,
+            is_saved: false,
+            company_owner_pubkey: Pubkey::new_unique(),
+            company_pubkey: Pubkey::new_unique(),
+            user_pubkey: Pubkey::new_unique(),
+            job_pubkey: Pubkey::new_unique(),
+            status: status.to_string(),
+            job_applied_at: 1,
+            is_paid: true,
+            paid_amount: 2,
+            paid_at: 3,
+            updated_at: 4,
+        }
+    }
+
+    #[test]
+    fn upgrade_keeps_the_legacy_fields() {
+        let legacy_state = legacy("in_progress");
+        let state = legacy_state.upgrade(254);
+
+        assert_eq!(
+            state,
+            WorkflowState {
+                discriminator: WorkflowState::DISCRIMINATOR,
+                version: WorkflowState::VERSION,
+                is_initialized: true,
+                archived: false,
+                is_saved: false,
+                status: WorkflowStatus::InProgress,
+                bump: 254,
+                company_owner_pubkey: legacy_state.company_owner_pubkey,
+                company_pubkey: legacy_state.company_pubkey,
+                user_pubkey: legacy_state.user_pubkey,
+                job_pubkey: legacy_state.job_pubkey,
+                job_applied_at: 1,
+                is_paid: true,
+                paid_amount: 2,
+                paid_at: 3,
+                updated_at: 4,
+                paid_mint: Pubkey::default(),
+                history: Vec::new(),
+            }
+        );
+        assert_eq!(WorkflowState::layout_version(&state.try_to_vec().unwrap()), Some(WorkflowState::VERSION));
+    }
 
-    
+    #[test]
+    fn upgrade_archives_unknown_legacy_statuses_as_saved() {
+        for status in ["", "hired", "Applied", "in progress"] {
+            let state = legacy(status).upgrade(255);
+
+            assert_eq!(state.status, WorkflowStatus::Saved);
+            assert!(state.archived);
+        }
+    }
+
+    #[test]
+    fn legacy_account_has_no_layout_version() {
+        let mut data = legacy("accepted").try_to_vec().unwrap();
+        data.resize(WorkflowStateV1::LEN, 0);
+
+        assert_eq!(WorkflowState::layout_version(&data), None);
+    }
+}
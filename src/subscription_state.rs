@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    program_pack::{IsInitialized, Sealed},
+    pubkey::Pubkey,
+};
+
+use crate::error::WorkflowError;
+
+const DAY_IN_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+/// Plans are declared from the shortest to the longest, renewals never move to a shorter plan.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum SubscriptionPlan {
+    PayNUse, //0 default, every application is paid for separately
+    SixMonths, //1
+    Yearly, //2
+    Forever, //3
+}
+
+impl SubscriptionPlan {
+    /// Validity added by a purchase of the plan, `None` when the plan never expires.
+    pub fn duration_in_millis(&self) -> Option<u64> {
+        match self {
+            SubscriptionPlan::PayNUse => Some(0),
+            SubscriptionPlan::SixMonths => Some(182 * DAY_IN_MILLIS),
+            SubscriptionPlan::Yearly => Some(365 * DAY_IN_MILLIS),
+            SubscriptionPlan::Forever => None,
+        }
+    }
+}
+
+/// Plan names of the legacy `CompanyInfoState.subscription_plan`.
+impl FromStr for SubscriptionPlan {
+    type Err = WorkflowError;
+
+    fn from_str(plan: &str) -> Result<Self, Self::Err> {
+        match plan {
+            "paynuse" => Ok(SubscriptionPlan::PayNUse),
+            "sixmonths" => Ok(SubscriptionPlan::SixMonths),
+            "yearly" => Ok(SubscriptionPlan::Yearly),
+            "forever" => Ok(SubscriptionPlan::Forever),
+            _ => Err(WorkflowError::UnknownSubscriptionPlan),
+        }
+    }
+}
+
+/// Subscription of a company sold by this program, PDA of [SUBSCRIPTION_STATE_ACCOUNT_PREFIX, company_info_state_account].
+#[derive(Debug, PartialEq, Clone)]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SubscriptionState {
+    pub is_initialized: bool, //1
+    pub company_pubkey: Pubkey, //32
+    pub plan: SubscriptionPlan, //1
+    pub purchased_on: u64, //8 => timestamp in unix format
+    pub valid_till: u64, //8 => timestamp in unix format, u64::MAX for 'forever'
+    pub paid_amount: u64, //8 lamports paid for the last purchase
+    pub bump: u8, //1 canonical bump of the subscription PDA
+}
+impl Sealed for SubscriptionState {}
+impl IsInitialized for SubscriptionState {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl SubscriptionState {
+    pub const LEN: usize = 1+32+1+8+8+8+1; //59
+
+    pub fn is_active(&self, timestamp: u64) -> bool {
+        self.is_initialized && self.plan != SubscriptionPlan::PayNUse && timestamp < self.valid_till
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(plan: SubscriptionPlan, valid_till: u64) -> SubscriptionState {
+        SubscriptionState {
+            is_initialized: true,
+            company_pubkey: Pubkey::new_unique(),
+            plan,
+            purchased_on: 0,
+            valid_till,
+            paid_amount: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn plan_durations() {
+        assert_eq!(SubscriptionPlan::PayNUse.duration_in_millis(), Some(0));
+        assert_eq!(SubscriptionPlan::SixMonths.duration_in_millis(), Some(182 * DAY_IN_MILLIS));
+        assert_eq!(SubscriptionPlan::Yearly.duration_in_millis(), Some(365 * DAY_IN_MILLIS));
+        assert_eq!(SubscriptionPlan::Forever.duration_in_millis(), None);
+    }
+
+    #[test]
+    fn subscription_is_active_until_valid_till() {
+        let yearly = subscription(SubscriptionPlan::Yearly, 1_000);
+
+        assert!(yearly.is_active(0));
+        assert!(yearly.is_active(999));
+        assert!(!yearly.is_active(1_000));
+        assert!(!SubscriptionState { is_initialized: false, ..yearly.clone() }.is_active(0));
+        assert!(!subscription(SubscriptionPlan::PayNUse, u64::MAX).is_active(0));
+    }
+
+    #[test]
+    fn legacy_plan_names() {
+        for (name, plan) in [
+            ("paynuse", SubscriptionPlan::PayNUse),
+            ("sixmonths", SubscriptionPlan::SixMonths),
+            ("yearly", SubscriptionPlan::Yearly),
+            ("forever", SubscriptionPlan::Forever),
+        ] {
+            assert_eq!(name.parse::<SubscriptionPlan>(), Ok(plan));
+        }
+        assert_eq!("monthly".parse::<SubscriptionPlan>(), Err(WorkflowError::UnknownSubscriptionPlan));
+    }
+
+    #[test]
+    fn subscription_fills_the_account() {
+        let data = subscription(SubscriptionPlan::Forever, u64::MAX).try_to_vec().unwrap();
+        assert_eq!(data.len(), SubscriptionState::LEN);
+    }
+}
@@ -0,0 +1,685 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    borsh::try_from_slice_unchecked,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::IsInitialized,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    applicant_info_state::ApplicantInfoState,
+    company_info_state::CompanyInfoHeader,
+    config_state::ConfigState,
+    contants::{
+        APPLICANT_STATE_ACCOUNT_PREFIX, COMPANY_STATE_ACCOUNT_PREFIX, CONFIG_STATE_ACCOUNT_PREFIX,
+        JOBPOST_STATE_ACCOUNT_PREFIX, SUBSCRIPTION_STATE_ACCOUNT_PREFIX, TREASURY_ACCOUNT_PREFIX,
+        WORKFLOW_STATE_ACCOUNT_PREFIX,
+    },
+    error::WorkflowError,
+    instruction::{program_data_address, SiblingBumps},
+    jobpost_info_state::JobPostHeader,
+    state::WorkflowState,
+    subscription_state::SubscriptionState,
+    workflow_status::WorkflowStatus,
+};
+
+/// Signer requirements checked on top of the shared workflow account validation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ValidationMode {
+    /// AddWorkflowState: signer is the candidate, the workflow account is not initialized yet.
+    Create,
+    /// UpdateWorkflowState, CloseWorkflowState: signer is the candidate or the company owner.
+    Participant,
+    /// UpdateWorkflowPaymentState: signer is the payment authority of the config.
+    PaymentAuthority,
+    /// PayForApplication, PayForApplicationWithToken: signer is the company owner of an unpaid workflow
+    /// of a company without an active subscription.
+    CompanyPayer,
+}
+
+/// Validated accounts shared by every instruction operating on a workflow state account.
+///
+/// Those instructions start with the same accounts:
+/// 0. `[signer]` owner account, writable when it pays for the instruction
+/// 1. `[]` company info state account
+/// 2. `[]` applicant info state account of the candidate
+/// 3. `[]` job post info state account
+/// 4. `[writable]` workflow state account
+/// 5. `[]` user info program
+/// 6. `[]` company info program
+/// 7. `[]` job post info program
+/// 8. `[]` system program
+/// 9. `[]` config state account
+///
+/// followed by the instruction specific accounts left in `remaining_accounts`.
+/// UpdateWorkflowPaymentState passes the logged in user account right after the owner account,
+/// which moves accounts 1 to 9 to indices 2 to 10.
+/// In `CompanyPayer` mode the subscription PDA of the company follows at index 10 and is consumed here.
+pub struct WorkflowAccounts<'a, 'b> {
+    pub owner_account: &'a AccountInfo<'b>,
+    pub company_info_state_account: &'a AccountInfo<'b>,
+    pub applicant_info_state_account: &'a AccountInfo<'b>,
+    pub jobpost_info_state_account: &'a AccountInfo<'b>,
+    pub workflow_info_state_account: &'a AccountInfo<'b>,
+    pub system_program_id: &'a AccountInfo<'b>,
+    pub remaining_accounts: &'a [AccountInfo<'b>],
+
+    pub config_state_data: ConfigState,
+    pub company_info_state_data: CompanyInfoHeader,
+    pub jobpost_info_state_data: JobPostHeader,
+    /// Empty state in `Create` mode, the account may not exist yet.
+    pub workflow_state_data: WorkflowState,
+    /// Canonical bump searched in `Create` mode, the stored bump otherwise.
+    pub workflow_nonce: u8,
+
+    /// Signer is the candidate of the workflow.
+    pub is_candidate: bool,
+    /// Signer is the owner of the company of the workflow.
+    pub is_company: bool,
+}
+
+impl<'a, 'b> WorkflowAccounts<'a, 'b> {
+    pub fn load(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+        mode: ValidationMode,
+        bumps: &SiblingBumps,
+    ) -> Result<Self, ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+
+        let owner_account = next_account_info(account_info_iter)?;
+        if mode == ValidationMode::PaymentAuthority {
+            let _logged_in_user_pubkey = next_account_info(account_info_iter)?;
+        }
+        let company_info_state_account = next_account_info(account_info_iter)?;
+        let applicant_info_state_account = next_account_info(account_info_iter)?;
+        let jobpost_info_state_account = next_account_info(account_info_iter)?;
+        let workflow_info_state_account = next_account_info(account_info_iter)?;
+
+        let user_info_program_id = next_account_info(account_info_iter)?;
+        let company_info_program_id = next_account_info(account_info_iter)?;
+        let jobpost_info_program_id = next_account_info(account_info_iter)?;
+        let system_program_id = next_account_info(account_info_iter)?;
+        let config_state_account = next_account_info(account_info_iter)?;
+
+        if !owner_account.is_signer {
+            msg!("Owner account is not a signer");
+            return Err(WorkflowError::OwnerNotSigner.into());
+        }
+
+        let config_state_data = load_config_state(program_id, config_state_account)?;
+        verify_sibling_programs(
+            &config_state_data,
+            user_info_program_id,
+            company_info_program_id,
+            jobpost_info_program_id,
+        )?;
+
+        let company_info_state_data =
+            load_company(&config_state_data, company_info_state_account, bumps.company)?;
+
+        //State: Verify Jobpost Info state account
+        verify_foreign_owner(
+            jobpost_info_state_account,
+            &config_state_data.jobpost_info_program_id,
+            WorkflowError::JobPostNotOwnedBySiblingProgram,
+        )?;
+
+        let jobpost_info_state_data =
+        JobPostHeader::unpack(&jobpost_info_state_account.data.borrow())
+            .map_err(|_| WorkflowError::InvalidJobPostData)?;
+
+        verify_pda(
+            jobpost_info_state_account,
+            &[
+                JOBPOST_STATE_ACCOUNT_PREFIX.as_bytes(),
+                jobpost_info_state_data.job_number.as_bytes(),
+                company_info_state_account.key.as_ref(),
+            ],
+            bumps.jobpost,
+            &config_state_data.jobpost_info_program_id,
+            WorkflowError::JobPostPdaMismatch,
+        )?;
+        //End: Verify Jobpost Info state account
+
+        //State: Verify Applicant state account
+        verify_foreign_owner(
+            applicant_info_state_account,
+            &config_state_data.user_info_program_id,
+            WorkflowError::ApplicantNotOwnedBySiblingProgram,
+        )?;
+
+        let applicant_info_state_data =
+        try_from_slice_unchecked::<ApplicantInfoState>(&applicant_info_state_account.data.borrow())
+            .map_err(|_| WorkflowError::InvalidApplicantInfoData)?;
+
+        if !applicant_info_state_data.is_initialized() {
+            msg!("Applicant Info State Account is not initialized");
+            return Err(WorkflowError::ApplicantNotInitialized.into());
+        }
+
+        verify_pda(
+            applicant_info_state_account,
+            &[
+                APPLICANT_STATE_ACCOUNT_PREFIX.as_bytes(),
+                applicant_info_state_data.owner_pubkey.as_ref(),
+            ],
+            bumps.applicant,
+            &config_state_data.user_info_program_id,
+            WorkflowError::ApplicantPdaMismatch,
+        )?;
+        //End: Verify Applicant state account
+
+        //State: Verify Workflow state account
+        let wokrflow_state_pda_seed = &[
+            WORKFLOW_STATE_ACCOUNT_PREFIX.as_bytes(),
+            jobpost_info_state_account.key.as_ref(),
+            applicant_info_state_account.key.as_ref(),
+        ];
+
+        //A new workflow has to be created at the canonical bump, only one workflow may exist per job and applicant
+        let (workflow_state_data, workflow_nonce) = if mode == ValidationMode::Create {
+            let (wokrflow_state_pda, workflow_nonce) =
+                Pubkey::find_program_address(wokrflow_state_pda_seed, program_id);
+
+            if wokrflow_state_pda != *workflow_info_state_account.key {
+                msg!("Invalid Workflow State PDA");
+                return Err(WorkflowError::WorkflowPdaMismatch.into());
+            }
+
+            (load_new_workflow_state(program_id, workflow_info_state_account)?, workflow_nonce)
+        } else {
+            let workflow_state_data = load_workflow_state(program_id, workflow_info_state_account)?;
+
+            verify_pda(
+                workflow_info_state_account,
+                wokrflow_state_pda_seed,
+                workflow_state_data.bump,
+                program_id,
+                WorkflowError::WorkflowPdaMismatch,
+            )?;
+
+            let workflow_nonce = workflow_state_data.bump;
+            (workflow_state_data, workflow_nonce)
+        };
+
+        if mode != ValidationMode::Create {
+            if workflow_state_data.job_pubkey != *jobpost_info_state_account.key {
+                msg!("Workflow state account does not belong to the jobpost_info_state_account");
+                return Err(WorkflowError::WorkflowJobMismatch.into());
+            }
+
+            if workflow_state_data.company_owner_pubkey != company_info_state_data.user_info_state_account_pubkey {
+                msg!("Workflow company owner state account does not match with the company owner");
+                return Err(WorkflowError::WorkflowCompanyOwnerMismatch.into());
+            }
+        }
+        //End: Verify Workflow state account
+
+        //State: Resolve the role of the signer
+        //applicant info state account gets created for the applicant and for the company owner as well,
+        //so the signer's own applicant PDA tells on which side of the workflow the signer is
+        //a wrong bump only yields an address that matches neither side
+        let signer_applicant_pda = Pubkey::create_program_address(
+            &[
+                APPLICANT_STATE_ACCOUNT_PREFIX.as_bytes(),
+                owner_account.key.as_ref(),
+                &[bumps.signer_applicant],
+            ],
+            &config_state_data.user_info_program_id,
+        )
+        .ok();
+
+        let (is_candidate, is_company) = if mode == ValidationMode::Create {
+            (
+                signer_applicant_pda == Some(*applicant_info_state_account.key),
+                signer_applicant_pda == Some(company_info_state_data.user_info_state_account_pubkey),
+            )
+        } else {
+            (
+                signer_applicant_pda == Some(workflow_state_data.user_pubkey),
+                signer_applicant_pda == Some(workflow_state_data.company_owner_pubkey),
+            )
+        };
+        //End: Resolve the role of the signer
+
+        match mode {
+            ValidationMode::Create => {
+                if !is_candidate {
+                    msg!("owner_pubkey of the applicant_info_state_data does not match the owner_account of the transaction");
+                    return Err(WorkflowError::ApplicantOwnerMismatch.into());
+                }
+            }
+            ValidationMode::Participant => {
+                if !is_candidate && !is_company {
+                    msg!("Workflow state account does not belong to the owner");
+                    return Err(WorkflowError::NotWorkflowParticipant.into());
+                }
+            }
+            ValidationMode::PaymentAuthority => {
+                if *owner_account.key != config_state_data.payment_authority {
+                    msg!("Unauthorized subscription modifier trying to update the subscription");
+                    return Err(WorkflowError::UnauthorizedPaymentModifier.into());
+                }
+            }
+            ValidationMode::CompanyPayer => {
+                if !is_company {
+                    msg!("Only the company owner can pay for the application");
+                    return Err(WorkflowError::UnauthorizedPayer.into());
+                }
+
+                if workflow_state_data.is_paid {
+                    msg!("Workflow is already paid");
+                    return Err(WorkflowError::WorkflowAlreadyPaid.into());
+                }
+
+                //Applications of a subscribed company are covered by its plan, as in add_workflow_state
+                let subscription_state_account = next_account_info(account_info_iter)?;
+                let timestamp = Clock::get()?.unix_timestamp as u64 * 1000;
+                if let Some(subscription) = active_subscription(
+                    program_id,
+                    company_info_state_account,
+                    subscription_state_account,
+                    timestamp,
+                )? {
+                    msg!("Company subscription is valid till {}", subscription.valid_till);
+                    return Err(WorkflowError::CompanySubscribed.into());
+                }
+            }
+        }
+
+        Ok(WorkflowAccounts {
+            owner_account,
+            company_info_state_account,
+            applicant_info_state_account,
+            jobpost_info_state_account,
+            workflow_info_state_account,
+            system_program_id,
+            remaining_accounts: account_info_iter.as_slice(),
+            config_state_data,
+            company_info_state_data,
+            jobpost_info_state_data,
+            workflow_state_data,
+            workflow_nonce,
+            is_candidate,
+            is_company,
+        })
+    }
+}
+
+/// Program owned, initialized workflow state.
+fn load_workflow_state(
+    program_id: &Pubkey,
+    workflow_info_state_account: &AccountInfo,
+) -> Result<WorkflowState, ProgramError> {
+    if workflow_info_state_account.owner != program_id {
+        msg!("Workflow info state account is not owned by the program");
+        return Err(WorkflowError::WorkflowNotOwnedByProgram.into());
+    }
+
+    let data = workflow_info_state_account.data.borrow();
+
+    match WorkflowState::layout_version(&data) {
+        Some(WorkflowState::VERSION) => {}
+        Some(version) => {
+            msg!("Workflow state account has layout version {}, expected {}, migrate it with MigrateWorkflowState", version, WorkflowState::VERSION);
+            return Err(WorkflowError::UnsupportedWorkflowVersion.into());
+        }
+        None => {
+            msg!("Workflow state account does not start with the workflow discriminator, legacy accounts have to be migrated with MigrateWorkflowState");
+            return Err(WorkflowError::InvalidWorkflowDiscriminator.into());
+        }
+    }
+
+    let workflow_state_data =
+    try_from_slice_unchecked::<WorkflowState>(&data)
+        .map_err(|_| WorkflowError::InvalidWorkflowData)?;
+
+    if !workflow_state_data.is_initialized() {
+        msg!("Workflow state account is not initialized");
+        return Err(WorkflowError::WorkflowNotInitialized.into());
+    }
+
+    Ok(workflow_state_data)
+}
+
+/// Workflow state of an account that is not created yet or not initialized.
+fn load_new_workflow_state(
+    program_id: &Pubkey,
+    workflow_info_state_account: &AccountInfo,
+) -> Result<WorkflowState, ProgramError> {
+    if workflow_info_state_account.owner != program_id {
+        return Ok(WorkflowState::default());
+    }
+
+    //Adding again would reset the status and wipe the recorded payment of the workflow,
+    //status changes have to go through UpdateWorkflowState.
+    //Workflow accounts are initialized on creation and zeroed on close, so any data left
+    //belongs to a workflow of the current or of the legacy layout
+    let is_initialized = workflow_info_state_account.data.borrow().iter().any(|byte| *byte != 0);
+
+    if is_initialized {
+        msg!("Workflow state account is already initialized");
+        return Err(WorkflowError::WorkflowAlreadyInitialized.into());
+    }
+
+    Ok(WorkflowState::default())
+}
+
+/// Verifies the config PDA with its stored bump and returns its initialized data.
+pub fn load_config_state(
+    program_id: &Pubkey,
+    config_state_account: &AccountInfo,
+) -> Result<ConfigState, ProgramError> {
+    if config_state_account.owner != program_id {
+        msg!("Config state account is not owned by the program");
+        return Err(WorkflowError::ConfigNotInitialized.into());
+    }
+
+    let config_state_data =
+    try_from_slice_unchecked::<ConfigState>(&config_state_account.data.borrow())
+        .map_err(|_| WorkflowError::InvalidConfigData)?;
+
+    if !config_state_data.is_initialized() {
+        msg!("Config state account is not initialized");
+        return Err(WorkflowError::ConfigNotInitialized.into());
+    }
+
+    verify_pda(
+        config_state_account,
+        &[CONFIG_STATE_ACCOUNT_PREFIX.as_bytes()],
+        config_state_data.bump,
+        program_id,
+        WorkflowError::ConfigPdaMismatch,
+    )?;
+
+    Ok(config_state_data)
+}
+
+/// Subscription of the company, `None` while no subscription account was created for it.
+pub fn load_subscription_state(
+    program_id: &Pubkey,
+    company_info_state_account: &AccountInfo,
+    subscription_state_account: &AccountInfo,
+) -> Result<Option<SubscriptionState>, ProgramError> {
+    if subscription_state_account.owner != program_id {
+        return Ok(None);
+    }
+
+    let subscription_state_data =
+    try_from_slice_unchecked::<SubscriptionState>(&subscription_state_account.data.borrow())
+        .map_err(|_| WorkflowError::InvalidSubscriptionData)?;
+
+    verify_pda(
+        subscription_state_account,
+        &[
+            SUBSCRIPTION_STATE_ACCOUNT_PREFIX.as_bytes(),
+            company_info_state_account.key.as_ref(),
+        ],
+        subscription_state_data.bump,
+        program_id,
+        WorkflowError::SubscriptionPdaMismatch,
+    )?;
+
+    Ok(Some(subscription_state_data))
+}
+
+/// Subscription of the company if it covers applications at `timestamp`.
+pub fn active_subscription(
+    program_id: &Pubkey,
+    company_info_state_account: &AccountInfo,
+    subscription_state_account: &AccountInfo,
+    timestamp: u64,
+) -> Result<Option<SubscriptionState>, ProgramError> {
+    let subscription_state_data =
+        load_subscription_state(program_id, company_info_state_account, subscription_state_account)?;
+
+    Ok(subscription_state_data.filter(|subscription| subscription.is_active(timestamp)))
+}
+
+/// Config data of an instruction signed by the config authority.
+pub fn load_config_as_admin(
+    program_id: &Pubkey,
+    authority_account: &AccountInfo,
+    config_state_account: &AccountInfo,
+) -> Result<ConfigState, ProgramError> {
+    let config_state_data = load_config_state(program_id, config_state_account)?;
+
+    if !authority_account.is_signer || *authority_account.key != config_state_data.authority {
+        msg!("Signer is not the config authority");
+        return Err(WorkflowError::UnauthorizedConfigAuthority.into());
+    }
+
+    Ok(config_state_data)
+}
+
+/// Signer has to be the upgrade authority recorded in the ProgramData account of the program.
+pub fn verify_upgrade_authority(
+    program_id: &Pubkey,
+    program_data_account: &AccountInfo,
+    authority_account: &AccountInfo,
+) -> ProgramResult {
+    if *program_data_account.owner != bpf_loader_upgradeable::id()
+        || *program_data_account.key != program_data_address(program_id)
+    {
+        msg!("Program data account does not belong to the program");
+        return Err(WorkflowError::InvalidProgramDataAccount.into());
+    }
+
+    let upgrade_authority_address = match bincode::deserialize(&program_data_account.data.borrow()) {
+        Ok(UpgradeableLoaderState::ProgramData { upgrade_authority_address, .. }) => upgrade_authority_address,
+        _ => {
+            msg!("Program data account holds no program data");
+            return Err(WorkflowError::InvalidProgramDataAccount.into());
+        }
+    };
+
+    if upgrade_authority_address != Some(*authority_account.key) {
+        msg!("Signer is not the upgrade authority of the program");
+        return Err(WorkflowError::UnauthorizedConfigInitializer.into());
+    }
+
+    Ok(())
+}
+
+/// Treasury PDA from the bump stored in the config.
+pub fn treasury_pda(program_id: &Pubkey, config_state_data: &ConfigState) -> Result<Pubkey, ProgramError> {
+    Pubkey::create_program_address(
+        &[TREASURY_ACCOUNT_PREFIX.as_bytes(), &[config_state_data.treasury_bump]],
+        program_id,
+    )
+    .map_err(|_| WorkflowError::TreasuryPdaMismatch.into())
+}
+
+/// Treasury account has to be the treasury PDA of the config.
+pub fn verify_treasury(
+    program_id: &Pubkey,
+    config_state_data: &ConfigState,
+    treasury_account: &AccountInfo,
+) -> ProgramResult {
+    if treasury_pda(program_id, config_state_data)? != *treasury_account.key {
+        msg!("Invalid Treasury PDA");
+        return Err(WorkflowError::TreasuryPdaMismatch.into());
+    }
+
+    Ok(())
+}
+
+/// Amount that arrived in the treasury between its balance before and after the transfer,
+/// which has to be the amount paid. The system and token programs always move the full amount,
+/// this keeps the recorded payment to what the treasury balance actually shows.
+pub fn verify_received_amount(balance_before: u64, balance_after: u64, amount: u64) -> Result<u64, ProgramError> {
+    let received_amount = balance_after
+        .checked_sub(balance_before)
+        .ok_or(WorkflowError::PaymentNotReceived)?;
+
+    if received_amount != amount {
+        msg!("Treasury received {}, expected {}", received_amount, amount);
+        return Err(WorkflowError::PaymentNotReceived.into());
+    }
+
+    Ok(received_amount)
+}
+
+/// Re-derives the PDA of an existing account from its bump, a single hash
+/// where `find_program_address` may try up to 255 bumps.
+/// Accounts still to be created have to be derived with `find_program_address`.
+pub fn verify_pda(
+    account: &AccountInfo,
+    seeds: &[&[u8]],
+    bump: u8,
+    program_id: &Pubkey,
+    error: WorkflowError,
+) -> ProgramResult {
+    let bump_seed = [bump];
+    let mut seeds_with_bump = seeds.to_vec();
+    seeds_with_bump.push(&bump_seed);
+
+    match Pubkey::create_program_address(&seeds_with_bump, program_id) {
+        Ok(pda) if pda == *account.key => Ok(()),
+        _ => {
+            msg!("Account {} does not match the PDA of bump {}", account.key, bump);
+            Err(error.into())
+        }
+    }
+}
+
+/// Program accounts passed by the caller have to be the sibling programs pinned in the config.
+pub fn verify_sibling_programs(
+    config_state_data: &ConfigState,
+    user_info_program_id: &AccountInfo,
+    company_info_program_id: &AccountInfo,
+    jobpost_info_program_id: &AccountInfo,
+) -> ProgramResult {
+    if *user_info_program_id.key != config_state_data.user_info_program_id
+        || *company_info_program_id.key != config_state_data.company_info_program_id
+        || *jobpost_info_program_id.key != config_state_data.jobpost_info_program_id
+    {
+        msg!("Sibling program accounts do not match the config");
+        return Err(WorkflowError::SiblingProgramMismatch.into());
+    }
+
+    Ok(())
+}
+
+/// Foreign state accounts are only trusted when owned by the program that writes them.
+pub fn verify_foreign_owner(
+    account: &AccountInfo,
+    expected_owner: &Pubkey,
+    error: WorkflowError,
+) -> ProgramResult {
+    if account.owner != expected_owner {
+        msg!("Account {} is not owned by {}", account.key, expected_owner);
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+/// Initialized company info header of a company account owned by the company info program
+/// and derived with `bump`.
+pub fn load_company(
+    config_state_data: &ConfigState,
+    company_info_state_account: &AccountInfo,
+    bump: u8,
+) -> Result<CompanyInfoHeader, ProgramError> {
+    verify_foreign_owner(
+        company_info_state_account,
+        &config_state_data.company_info_program_id,
+        WorkflowError::CompanyNotOwnedBySiblingProgram,
+    )?;
+
+    let company_info_state_data =
+    CompanyInfoHeader::unpack(&company_info_state_account.data.borrow())
+        .map_err(|_| WorkflowError::InvalidCompanyInfoData)?;
+
+    if !company_info_state_data.is_initialized() {
+        msg!("Company info state account is not initialized");
+        return Err(WorkflowError::CompanyNotInitialized.into());
+    }
+
+    verify_company_pda(
+        company_info_state_account,
+        &company_info_state_data,
+        &config_state_data.company_info_program_id,
+        bump,
+    )?;
+
+    Ok(company_info_state_data)
+}
+
+/// Company info state account is the PDA of [COMPANY_STATE_ACCOUNT_PREFIX, company_seq_number, owner's applicant info state account].
+pub fn verify_company_pda(
+    company_info_state_account: &AccountInfo,
+    company_info_state_data: &CompanyInfoHeader,
+    company_info_program_id: &Pubkey,
+    bump: u8,
+) -> ProgramResult {
+    let company_info_pda = Pubkey::create_program_address(
+        &[
+            COMPANY_STATE_ACCOUNT_PREFIX.as_bytes(),
+            company_info_state_data.company_seq_number.as_bytes(),
+            company_info_state_data.user_info_state_account_pubkey.as_ref(),
+            &[bump],
+        ],
+        company_info_program_id,
+    )
+    .ok();
+
+    if company_info_pda != Some(*company_info_state_account.key) {
+        msg!(
+            "Company Info State Account does not match the PDA of seq number {} and owner {}",
+            company_info_state_data.company_seq_number,
+            company_info_state_data.user_info_state_account_pubkey
+        );
+        return Err(WorkflowError::CompanyPdaMismatch.into());
+    }
+
+    Ok(())
+}
+
+/// Archived job posts and companies take no new applications,
+/// saving them is only allowed when enabled in the config.
+pub fn verify_open_for_applications(
+    jobpost_info_state_data: &JobPostHeader,
+    company_info_state_data: &CompanyInfoHeader,
+    status: WorkflowStatus,
+    allow_saving_archived_jobs: bool,
+) -> ProgramResult {
+    if status == WorkflowStatus::Saved && allow_saving_archived_jobs {
+        return Ok(());
+    }
+
+    if jobpost_info_state_data.archived {
+        msg!("Job post is archived");
+        return Err(WorkflowError::JobPostArchived.into());
+    }
+
+    if company_info_state_data.archived {
+        msg!("Company is archived");
+        return Err(WorkflowError::CompanyArchived.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn received_amount_has_to_match_the_payment() {
+        let not_received = Err(ProgramError::from(WorkflowError::PaymentNotReceived));
+
+        assert_eq!(verify_received_amount(5, 15, 10), Ok(10));
+        assert_eq!(verify_received_amount(5, 14, 10), not_received);
+        assert_eq!(verify_received_amount(5, 16, 10), not_received);
+        assert_eq!(verify_received_amount(5, 5, 10), not_received);
+        assert_eq!(verify_received_amount(5, 4, 10), not_received);
+    }
+}
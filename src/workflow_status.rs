@@ -0,0 +1,196 @@
+use std::{convert::TryFrom, str::FromStr};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::error::WorkflowError;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum WorkflowStatus {
+    #[default]
+    Saved, //0
+    Applied, //1
+    InProgress, //2
+    Accepted, //3
+    Rejected, //4
+    Withdraw, //5
+}
+
+/// Side of the application the signer of an instruction is acting for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum WorkflowRole {
+    Candidate,
+    Company,
+}
+
+impl WorkflowStatus {
+    /// Status string of the legacy `WorkflowState` layout and of the logs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkflowStatus::Saved => "saved",
+            WorkflowStatus::Applied => "applied",
+            WorkflowStatus::InProgress => "in_progress",
+            WorkflowStatus::Accepted => "accepted",
+            WorkflowStatus::Rejected => "rejected",
+            WorkflowStatus::Withdraw => "withdraw",
+        }
+    }
+
+    /// A workflow can only be created as a saved job or as a fresh application.
+    pub fn is_initial(&self) -> bool {
+        matches!(self, WorkflowStatus::Saved | WorkflowStatus::Applied)
+    }
+
+    /// No transition leaves 'accepted', 'rejected' or 'withdraw'.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            WorkflowStatus::Accepted | WorkflowStatus::Rejected | WorkflowStatus::Withdraw
+        )
+    }
+
+    /// Candidates manage their own application, the company decides on it.
+    pub fn can_be_set_by(&self, role: WorkflowRole) -> bool {
+        match role {
+            WorkflowRole::Candidate => matches!(
+                self,
+                WorkflowStatus::Saved | WorkflowStatus::Applied | WorkflowStatus::Withdraw
+            ),
+            WorkflowRole::Company => matches!(
+                self,
+                WorkflowStatus::InProgress | WorkflowStatus::Accepted | WorkflowStatus::Rejected
+            ),
+        }
+    }
+
+    /// Transition table enforced by `update_workflow_state`.
+    /// Re-sending the current status is allowed so the flags can be updated on their own.
+    pub fn can_transition_to(&self, next: WorkflowStatus) -> bool {
+        use WorkflowStatus::*;
+
+        if *self == next {
+            return true;
+        }
+
+        matches!(
+            (self, next),
+            (Saved, Applied)
+                | (Saved, Withdraw)
+                | (Applied, InProgress)
+                | (Applied, Accepted)
+                | (Applied, Rejected)
+                | (Applied, Withdraw)
+                | (InProgress, Accepted)
+                | (InProgress, Rejected)
+                | (InProgress, Withdraw)
+        )
+    }
+}
+
+/// Status byte as stored in `WorkflowState.status`.
+impl TryFrom<u8> for WorkflowStatus {
+    type Error = WorkflowError;
+
+    fn try_from(status: u8) -> Result<Self, Self::Error> {
+        match status {
+            0 => Ok(WorkflowStatus::Saved),
+            1 => Ok(WorkflowStatus::Applied),
+            2 => Ok(WorkflowStatus::InProgress),
+            3 => Ok(WorkflowStatus::Accepted),
+            4 => Ok(WorkflowStatus::Rejected),
+            5 => Ok(WorkflowStatus::Withdraw),
+            _ => Err(WorkflowError::UnknownStatus),
+        }
+    }
+}
+
+impl FromStr for WorkflowStatus {
+    type Err = WorkflowError;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        match status {
+            "saved" => Ok(WorkflowStatus::Saved),
+            "applied" => Ok(WorkflowStatus::Applied),
+            "in_progress" => Ok(WorkflowStatus::InProgress),
+            "accepted" => Ok(WorkflowStatus::Accepted),
+            "rejected" => Ok(WorkflowStatus::Rejected),
+            "withdraw" => Ok(WorkflowStatus::Withdraw),
+            _ => Err(WorkflowError::UnknownStatus),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [WorkflowStatus; 6] = [
+        WorkflowStatus::Saved,
+        WorkflowStatus::Applied,
+        WorkflowStatus::InProgress,
+        WorkflowStatus::Accepted,
+        WorkflowStatus::Rejected,
+        WorkflowStatus::Withdraw,
+    ];
+
+    #[test]
+    fn transition_table() {
+        use WorkflowStatus::*;
+
+        let allowed = [
+            (Saved, Applied),
+            (Saved, Withdraw),
+            (Applied, InProgress),
+            (Applied, Accepted),
+            (Applied, Rejected),
+            (Applied, Withdraw),
+            (InProgress, Accepted),
+            (InProgress, Rejected),
+            (InProgress, Withdraw),
+        ];
+
+        for from in ALL {
+            for to in ALL {
+                let expected = from == to || allowed.contains(&(from, to));
+                assert_eq!(from.can_transition_to(to), expected, "{:?} -> {:?}", from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn final_statuses_only_transition_to_themselves() {
+        for from in ALL.iter().filter(|status| status.is_final()) {
+            for to in ALL {
+                assert_eq!(from.can_transition_to(to), *from == to);
+            }
+        }
+    }
+
+    #[test]
+    fn statuses_are_split_between_the_candidate_and_the_company() {
+        use WorkflowStatus::*;
+
+        for status in ALL {
+            let candidate = status.can_be_set_by(WorkflowRole::Candidate);
+            let company = status.can_be_set_by(WorkflowRole::Company);
+            assert_eq!(candidate, matches!(status, Saved | Applied | Withdraw), "{:?}", status);
+            assert_ne!(candidate, company, "{:?}", status);
+        }
+    }
+
+    #[test]
+    fn initial_statuses() {
+        let initial: Vec<_> = ALL.iter().filter(|status| status.is_initial()).collect();
+        assert_eq!(initial, [&WorkflowStatus::Saved, &WorkflowStatus::Applied]);
+    }
+
+    #[test]
+    fn status_byte_and_string_round_trip() {
+        for status in ALL {
+            assert_eq!(WorkflowStatus::try_from(status as u8), Ok(status));
+            assert_eq!(status.as_str().parse::<WorkflowStatus>(), Ok(status));
+        }
+        assert_eq!(WorkflowStatus::try_from(6), Err(WorkflowError::UnknownStatus));
+        assert_eq!("archived".parse::<WorkflowStatus>(), Err(WorkflowError::UnknownStatus));
+    }
+}
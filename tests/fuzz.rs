@@ -0,0 +1,390 @@
+//! Property based fuzzing of the decoders fed with attacker controlled bytes: instruction data
+//! and the data of the workflow and sibling program accounts.
+//!
+//! Inputs are either arbitrary bytes or valid encodings with random bytes overwritten and a
+//! random length cut off, so the decoders are driven past the first field as well.
+//! Every property asserts that decoding never panics, and that whatever decodes serializes
+//! back within the account it was read from.
+//!
+//! Run longer with `PROPTEST_CASES=100000 cargo test --test fuzz`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use jobsonchain_workflow_program::{
+    applicant_info_state::ApplicantInfoState,
+    company_info_state::{CompanyInfoHeader, CompanyInfoState},
+    instruction::WorkflowStateInstruction,
+    jobpost_info_state::{JobPostHeader, JobPostState},
+    state::{WorkflowHistoryEntry, WorkflowState, WorkflowStateV1, WorkflowStateView},
+    workflow_status::WorkflowStatus,
+};
+use proptest::{collection::vec, prelude::*, sample::Index};
+use solana_program::{borsh::try_from_slice_unchecked, pubkey::Pubkey};
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+fn short_string() -> impl Strategy<Value = String> {
+    "[a-z0-9_ ]{0,24}"
+}
+
+/// Number of `WorkflowStateInstruction` variants: the first tag Borsh rejects as an unknown
+/// variant index before it reads any payload.
+fn instruction_tags() -> u8 {
+    let is_known = |tag: &u8| match WorkflowStateInstruction::try_from_slice(&[*tag]) {
+        Ok(_) => true,
+        Err(error) => !error.to_string().starts_with("Unexpected variant index"),
+    };
+
+    (0..=u8::MAX).take_while(is_known).count() as u8
+}
+
+fn workflow_status() -> impl Strategy<Value = WorkflowStatus> {
+    (0u8..6).prop_map(|status| WorkflowStatus::try_from(status).unwrap())
+}
+
+/// Byte overwrites and an optional cut applied to a valid encoding.
+#[derive(Debug, Clone)]
+struct Corruption {
+    flips: Vec<(Index, u8)>,
+    cut: Option<Index>,
+}
+
+fn corruption() -> impl Strategy<Value = Corruption> {
+    (vec((any::<Index>(), any::<u8>()), 0..8), proptest::option::of(any::<Index>()))
+        .prop_map(|(flips, cut)| Corruption { flips, cut })
+}
+
+impl Corruption {
+    /// `data` padded to the account size `len`, then corrupted.
+    fn apply(&self, mut data: Vec<u8>, len: usize) -> Vec<u8> {
+        if data.len() < len {
+            data.resize(len, 0);
+        }
+
+        for (index, byte) in &self.flips {
+            let i = index.index(data.len());
+            data[i] = *byte;
+        }
+
+        if let Some(cut) = &self.cut {
+            data.truncate(cut.index(data.len() + 1));
+        }
+
+        data
+    }
+}
+
+fn applicant_info_state() -> impl Strategy<Value = ApplicantInfoState> {
+    (any::<bool>(), pubkey(), any::<(u64, u64)>(), vec(short_string(), 9), vec(short_string(), 0..10), any::<[bool; 6]>())
+        .prop_map(|(is_initialized, owner_pubkey, (created_at, updated_at), strings, skills, flags)| {
+            let mut strings = strings.into_iter();
+            let mut next = || strings.next().unwrap();
+
+            ApplicantInfoState {
+                is_initialized,
+                owner_pubkey,
+                created_at,
+                updated_at,
+                username: next(),
+                name: next(),
+                address: next(),
+                image_uri: next(),
+                bio: next(),
+                skills,
+                designation: next(),
+                current_employment_status: next(),
+                can_join_in: next(),
+                user_type: next(),
+                is_company_profile_complete: flags[0],
+                is_overview_complete: flags[1],
+                is_projects_complete: flags[2],
+                is_contact_info_complete: flags[3],
+                is_education_complete: flags[4],
+                is_work_experience_complete: flags[5],
+            }
+        })
+}
+
+fn company_info_state() -> impl Strategy<Value = CompanyInfoState> {
+    (any::<(bool, bool)>(), pubkey(), any::<[u64; 4]>(), vec(short_string(), 22))
+        .prop_map(|((is_initialized, archived), user_info_state_account_pubkey, numbers, strings)| {
+            let mut strings = strings.into_iter();
+            let mut next = || strings.next().unwrap();
+
+            CompanyInfoState {
+                is_initialized,
+                archived,
+                user_info_state_account_pubkey,
+                created_at: numbers[0],
+                updated_at: numbers[1],
+                username: next(),
+                name: next(),
+                logo_uri: next(),
+                domain: next(),
+                company_type: next(),
+                company_size: next(),
+                company_stage: next(),
+                funding_amount: next(),
+                funding_currency: next(),
+                image_uri: next(),
+                cover_image_uri: next(),
+                founded_in: next(),
+                employee_size: next(),
+                address: next(),
+                description: next(),
+                website: next(),
+                linkedin: next(),
+                twitter: next(),
+                facebook: next(),
+                instagram: next(),
+                subscription_plan: next(),
+                subscription_purchased_on: numbers[2],
+                subscription_valid_till: numbers[3],
+                company_seq_number: next(),
+            }
+        })
+}
+
+fn jobpost_state() -> impl Strategy<Value = JobPostState> {
+    (
+        any::<(bool, bool)>(),
+        (pubkey(), pubkey()),
+        any::<[u64; 5]>(),
+        vec(short_string(), 11),
+        (vec(short_string(), 0..4), vec(short_string(), 0..10)),
+    )
+        .prop_map(|((is_initialized, archived), (owner_pubkey, company_pubkey), numbers, strings, (category, skills))| {
+            let mut strings = strings.into_iter();
+            let mut next = || strings.next().unwrap();
+
+            JobPostState {
+                is_initialized,
+                archived,
+                owner_pubkey,
+                company_pubkey,
+                created_at: numbers[0],
+                updated_at: numbers[1],
+                job_title: next(),
+                short_description: next(),
+                long_description: next(),
+                category,
+                job_type: next(),
+                currency_type: next(),
+                currency: next(),
+                min_salary: numbers[2],
+                max_salary: numbers[3],
+                experience_in_months: numbers[4],
+                skills,
+                qualification: next(),
+                job_location_type: next(),
+                country: next(),
+                city: next(),
+                job_number: next(),
+            }
+        })
+}
+
+fn history_entry() -> impl Strategy<Value = WorkflowHistoryEntry> {
+    (workflow_status(), pubkey(), any::<u64>()).prop_map(|(status, actor, timestamp)| WorkflowHistoryEntry {
+        status,
+        actor,
+        timestamp,
+    })
+}
+
+fn workflow_state() -> impl Strategy<Value = WorkflowState> {
+    (
+        (any::<[bool; 4]>(), workflow_status(), any::<u8>()),
+        (pubkey(), pubkey(), pubkey(), pubkey(), pubkey()),
+        any::<[u64; 4]>(),
+        vec(history_entry(), 0..=WorkflowState::MAX_HISTORY),
+    )
+        .prop_map(|((flags, status, bump), keys, numbers, history)| WorkflowState {
+            discriminator: WorkflowState::DISCRIMINATOR,
+            version: WorkflowState::VERSION,
+            is_initialized: flags[0],
+            archived: flags[1],
+            is_saved: flags[2],
+            status,
+            bump,
+            company_owner_pubkey: keys.0,
+            company_pubkey: keys.1,
+            user_pubkey: keys.2,
+            job_pubkey: keys.3,
+            job_applied_at: numbers[0],
+            is_paid: flags[3],
+            paid_amount: numbers[1],
+            paid_at: numbers[2],
+            updated_at: numbers[3],
+            paid_mint: keys.4,
+            history,
+        })
+}
+
+fn workflow_state_v1() -> impl Strategy<Value = WorkflowStateV1> {
+    let status = prop_oneof![
+        workflow_status().prop_map(|status| status.as_str().to_string()),
+        short_string(),
+    ];
+
+    (any::<[bool; 4]>(), (pubkey(), pubkey(), pubkey(), pubkey()), status, any::<[u64; 4]>()).prop_map(
+        |(flags, keys, status, numbers)| WorkflowStateV1 {
+            is_initialized: flags[0],
+            archived: flags[1],
+            is_saved: flags[2],
+            company_owner_pubkey: keys.0,
+            company_pubkey: keys.1,
+            user_pubkey: keys.2,
+            job_pubkey: keys.3,
+            status,
+            job_applied_at: numbers[0],
+            is_paid: flags[3],
+            paid_amount: numbers[1],
+            paid_at: numbers[2],
+            updated_at: numbers[3],
+        },
+    )
+}
+
+/// Decoded state has to fit back into the account it was read from.
+fn assert_fits<T: BorshSerialize>(state: &T, len: usize) {
+    let mut account = vec![0u8; len];
+    assert!(state.serialize(&mut &mut account[..]).is_ok());
+}
+
+fn check_instruction(data: &[u8]) {
+    if let Ok(instruction) = WorkflowStateInstruction::unpack(data) {
+        assert_eq!(instruction.pack(), data);
+    }
+}
+
+fn check_applicant(data: &[u8]) {
+    if let Ok(state) = try_from_slice_unchecked::<ApplicantInfoState>(data) {
+        assert_fits(&state, data.len());
+    }
+}
+
+fn check_company(data: &[u8]) {
+    let header = CompanyInfoHeader::unpack(data);
+
+    if let Ok(state) = try_from_slice_unchecked::<CompanyInfoState>(data) {
+        assert_fits(&state, data.len());
+        assert_eq!(
+            header.unwrap(),
+            CompanyInfoHeader {
+                is_initialized: state.is_initialized,
+                archived: state.archived,
+                user_info_state_account_pubkey: state.user_info_state_account_pubkey,
+                subscription_plan: state.subscription_plan,
+                subscription_purchased_on: state.subscription_purchased_on,
+                subscription_valid_till: state.subscription_valid_till,
+                company_seq_number: state.company_seq_number,
+            }
+        );
+    }
+}
+
+fn check_jobpost(data: &[u8]) {
+    let header = JobPostHeader::unpack(data);
+
+    if let Ok(state) = try_from_slice_unchecked::<JobPostState>(data) {
+        assert_fits(&state, data.len());
+        assert_eq!(
+            header.unwrap(),
+            JobPostHeader {
+                is_initialized: state.is_initialized,
+                archived: state.archived,
+                owner_pubkey: state.owner_pubkey,
+                company_pubkey: state.company_pubkey,
+                job_number: state.job_number,
+            }
+        );
+    }
+}
+
+fn check_workflow(data: &[u8], actor: Pubkey, timestamp: u64) {
+    let _version = WorkflowState::layout_version(data);
+
+    let view = WorkflowStateView::new(data);
+    if let Some(view) = &view {
+        let _status = view.status();
+        for index in 0..=view.history_len().min(WorkflowState::MAX_HISTORY) {
+            let _entry = view.history_entry(index);
+        }
+    }
+
+    if let Ok(mut state) = try_from_slice_unchecked::<WorkflowState>(data) {
+        if let Some(view) = view {
+            assert_eq!(view.status(), Ok(state.status));
+            assert_eq!(view.paid_amount(), state.paid_amount);
+            assert_eq!(view.history_len(), state.history.len());
+        }
+
+        //what every status change does before writing the account back,
+        //workflow accounts are allocated with exactly WorkflowState::LEN bytes
+        state.record_status(WorkflowStatus::Applied, actor, timestamp);
+        if data.len() <= WorkflowState::LEN {
+            assert_fits(&state, WorkflowState::LEN);
+        }
+    }
+}
+
+fn check_workflow_v1(data: &[u8], bump: u8) {
+    if let Ok(legacy) = try_from_slice_unchecked::<WorkflowStateV1>(data) {
+        assert_fits(&legacy.upgrade(bump), WorkflowState::LEN);
+    }
+}
+
+proptest! {
+    #[test]
+    fn unpack_arbitrary_instruction_data(data in vec(any::<u8>(), 0..256)) {
+        check_instruction(&data);
+    }
+
+    #[test]
+    fn unpack_instruction_data_with_a_known_tag(tag in 0..instruction_tags(), payload in vec(any::<u8>(), 0..160)) {
+        let mut data = vec![tag];
+        data.extend(payload);
+        check_instruction(&data);
+    }
+
+    #[test]
+    fn decode_arbitrary_account_data(data in vec(any::<u8>(), 0..600), actor in pubkey(), timestamp in any::<u64>(), bump in any::<u8>()) {
+        check_applicant(&data);
+        check_company(&data);
+        check_jobpost(&data);
+        check_workflow(&data, actor, timestamp);
+        check_workflow_v1(&data, bump);
+    }
+
+    #[test]
+    fn decode_corrupted_applicant(state in applicant_info_state(), corruption in corruption()) {
+        let data = corruption.apply(state.try_to_vec().unwrap(), ApplicantInfoState::LEN);
+        check_applicant(&data);
+    }
+
+    #[test]
+    fn decode_corrupted_company(state in company_info_state(), corruption in corruption()) {
+        let data = corruption.apply(state.try_to_vec().unwrap(), CompanyInfoState::LEN);
+        check_company(&data);
+    }
+
+    #[test]
+    fn decode_corrupted_jobpost(state in jobpost_state(), corruption in corruption()) {
+        let data = corruption.apply(state.try_to_vec().unwrap(), JobPostState::LEN);
+        check_jobpost(&data);
+    }
+
+    #[test]
+    fn decode_corrupted_workflow(state in workflow_state(), corruption in corruption(), actor in pubkey(), timestamp in any::<u64>()) {
+        let data = corruption.apply(state.try_to_vec().unwrap(), WorkflowState::LEN);
+        check_workflow(&data, actor, timestamp);
+    }
+
+    #[test]
+    fn decode_corrupted_legacy_workflow(state in workflow_state_v1(), corruption in corruption(), bump in any::<u8>()) {
+        let data = corruption.apply(state.try_to_vec().unwrap(), WorkflowStateV1::LEN);
+        check_workflow_v1(&data, bump);
+    }
+}
@@ -0,0 +1,179 @@
+//! Wire format of `WorkflowStateInstruction`. The tags of the original instructions are kept,
+//! their payloads are not: data encoded for the original payloads is rejected.
+
+use borsh::BorshSerialize;
+use jobsonchain_workflow_program::{
+    config_state::{PaymentMint, SubscriptionPrices},
+    error::WorkflowError,
+    instruction::{AuthorityType, SiblingBumps, WorkflowStateInstruction},
+    subscription_state::SubscriptionPlan,
+    workflow_status::WorkflowStatus,
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+const BUMPS: SiblingBumps = SiblingBumps {
+    company: 255,
+    jobpost: 254,
+    applicant: 253,
+    signer_applicant: 252,
+};
+
+fn pubkey(tag: u8) -> Pubkey {
+    Pubkey::new_from_array([tag; 32])
+}
+
+#[test]
+fn workflow_instruction_tags_and_layout() {
+    let add = WorkflowStateInstruction::AddWorkflowState {
+        status: WorkflowStatus::Applied,
+        bumps: BUMPS,
+    };
+    assert_eq!(add.pack(), vec![0, 1, 255, 254, 253, 252]);
+
+    let update = WorkflowStateInstruction::UpdateWorkflowState {
+        archived: true,
+        is_saved: false,
+        status: WorkflowStatus::Rejected,
+        bumps: BUMPS,
+    };
+    assert_eq!(
+        update.pack(),
+        vec![1, 1, 0, WorkflowStatus::Rejected as u8, 255, 254, 253, 252]
+    );
+
+    let payment = WorkflowStateInstruction::UpdateWorkflowPaymentState {
+        is_paid: true,
+        paid_amount: 0x0102030405060708,
+        bumps: BUMPS,
+    };
+    assert_eq!(
+        payment.pack(),
+        vec![2, 1, 8, 7, 6, 5, 4, 3, 2, 1, 255, 254, 253, 252]
+    );
+}
+
+#[test]
+fn every_instruction_round_trips() {
+    let instructions = vec![
+        WorkflowStateInstruction::AddWorkflowState {
+            status: WorkflowStatus::Saved,
+            bumps: BUMPS,
+        },
+        WorkflowStateInstruction::UpdateWorkflowState {
+            archived: false,
+            is_saved: true,
+            status: WorkflowStatus::InProgress,
+            bumps: BUMPS,
+        },
+        WorkflowStateInstruction::UpdateWorkflowPaymentState {
+            is_paid: false,
+            paid_amount: u64::MAX,
+            bumps: BUMPS,
+        },
+        WorkflowStateInstruction::CloseWorkflowState { bumps: BUMPS },
+        WorkflowStateInstruction::InitializeConfig {
+            payment_authority: pubkey(1),
+            user_info_program_id: pubkey(2),
+            company_info_program_id: pubkey(3),
+            jobpost_info_program_id: pubkey(4),
+        },
+        WorkflowStateInstruction::SetAuthority {
+            authority_type: AuthorityType::Payment,
+            new_authority: pubkey(5),
+        },
+        WorkflowStateInstruction::AcceptAuthority {
+            authority_type: AuthorityType::Admin,
+        },
+        WorkflowStateInstruction::PayForApplication {
+            amount: 1_000_000,
+            bumps: BUMPS,
+        },
+        WorkflowStateInstruction::SetPaymentMints {
+            mints: vec![
+                PaymentMint {
+                    mint: pubkey(6),
+                    application_price: 1,
+                },
+                PaymentMint {
+                    mint: pubkey(7),
+                    application_price: 2,
+                },
+            ],
+        },
+        WorkflowStateInstruction::PayForApplicationWithToken {
+            amount: 42,
+            bumps: BUMPS,
+        },
+        WorkflowStateInstruction::SetSubscriptionPrices {
+            prices: SubscriptionPrices::default(),
+        },
+        WorkflowStateInstruction::PurchaseSubscription {
+            plan: SubscriptionPlan::Yearly,
+            company_bump: 250,
+            signer_applicant_bump: 249,
+        },
+        WorkflowStateInstruction::SetSiblingPrograms {
+            user_info_program_id: pubkey(8),
+            company_info_program_id: pubkey(9),
+            jobpost_info_program_id: pubkey(10),
+        },
+        WorkflowStateInstruction::SetAllowSavingArchivedJobs { allow: true },
+        WorkflowStateInstruction::MigrateWorkflowState,
+        WorkflowStateInstruction::SetApplicationPrice { price: 5_000_000 },
+        WorkflowStateInstruction::ImportLegacySubscription { company_bump: 248 },
+        WorkflowStateInstruction::WithdrawTreasury { amount: 7 },
+        WorkflowStateInstruction::WithdrawTreasuryTokens { amount: 8 },
+    ];
+
+    for (tag, instruction) in instructions.into_iter().enumerate() {
+        let data = instruction.pack();
+        assert_eq!(data[0] as usize, tag);
+        assert_eq!(WorkflowStateInstruction::unpack(&data), Ok(instruction));
+    }
+}
+
+#[test]
+fn malformed_instruction_data_is_rejected() {
+    let invalid = Err(ProgramError::from(WorkflowError::InvalidInstruction));
+    let mut trailing = WorkflowStateInstruction::AcceptAuthority {
+        authority_type: AuthorityType::Payment,
+    }
+    .pack();
+    trailing.push(0);
+
+    for data in [vec![], vec![19], vec![0, 1, 255], trailing] {
+        assert_eq!(WorkflowStateInstruction::unpack(&data), invalid);
+    }
+}
+
+/// Instruction data as encoded before statuses became a byte and sibling bumps were appended.
+fn original_payload(tag: u8, payload: impl BorshSerialize) -> Vec<u8> {
+    let mut data = vec![tag];
+    data.extend(payload.try_to_vec().unwrap());
+    data
+}
+
+#[test]
+fn original_workflow_instruction_payloads_are_rejected() {
+    let invalid = Err(ProgramError::from(WorkflowError::InvalidInstruction));
+
+    for status in [
+        "saved",
+        "applied",
+        "in_progress",
+        "accepted",
+        "rejected",
+        "withdraw",
+    ] {
+        let status = status.to_string();
+
+        let add = original_payload(0, status.clone());
+        assert_eq!(WorkflowStateInstruction::unpack(&add), invalid);
+
+        let update = original_payload(1, (true, false, status));
+        assert_eq!(WorkflowStateInstruction::unpack(&update), invalid);
+    }
+
+    let payment = original_payload(2, (true, 1_000_000u64));
+    assert_eq!(WorkflowStateInstruction::unpack(&payment), invalid);
+}
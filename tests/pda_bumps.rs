@@ -0,0 +1,242 @@
+//! Verification of the PDAs of a workflow instruction from the bumps stored in state and
+//! passed in `SiblingBumps`, against the addresses `find_program_address` derives, and the
+//! compute cost of both.
+//!
+//! On chain every `create_program_address` costs 1500 compute units and `find_program_address`
+//! pays that once for every bump it tries, starting from 255. The program is not run under the
+//! BPF loader here, so the benchmark counts the hashes each approach needs for real addresses
+//! and prices them with the syscall cost.
+//!
+//! Run with `cargo test --test pda_bumps -- --nocapture` to print the numbers.
+
+use jobsonchain_workflow_program::{
+    contants::{
+        APPLICANT_STATE_ACCOUNT_PREFIX, COMPANY_STATE_ACCOUNT_PREFIX, CONFIG_STATE_ACCOUNT_PREFIX,
+        JOBPOST_STATE_ACCOUNT_PREFIX, WORKFLOW_STATE_ACCOUNT_PREFIX,
+    },
+    error::WorkflowError,
+    validation::verify_pda,
+};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+const CREATE_PROGRAM_ADDRESS_UNITS: u64 = 1500;
+const SAMPLES: u8 = 64;
+
+fn pubkey(tag: u8, i: u8) -> Pubkey {
+    let mut bytes = [tag; 32];
+    bytes[0] = i;
+    Pubkey::new_from_array(bytes)
+}
+
+/// Accounts of a workflow whose candidate, company owner, company and job post are numbered `i`.
+struct Sample {
+    program_id: Pubkey,
+    user_info_program_id: Pubkey,
+    company_info_program_id: Pubkey,
+    jobpost_info_program_id: Pubkey,
+    candidate: Pubkey,
+    company_owner: Pubkey,
+    company_seq_number: String,
+    job_number: String,
+    applicant: Pubkey,
+    owner_applicant: Pubkey,
+    company: Pubkey,
+    jobpost: Pubkey,
+}
+
+impl Sample {
+    fn new(i: u8) -> Self {
+        let mut sample = Sample {
+            program_id: pubkey(1, 0),
+            user_info_program_id: pubkey(2, 0),
+            company_info_program_id: pubkey(3, 0),
+            jobpost_info_program_id: pubkey(4, 0),
+            candidate: pubkey(5, i),
+            company_owner: pubkey(6, i),
+            company_seq_number: format!("{}", i),
+            job_number: format!("{}", i),
+            applicant: Pubkey::default(),
+            owner_applicant: Pubkey::default(),
+            company: Pubkey::default(),
+            jobpost: Pubkey::default(),
+        };
+
+        sample.applicant = sample.address(&sample.applicant_seeds(), &sample.user_info_program_id);
+        sample.owner_applicant = sample.address(
+            &sample.owner_applicant_seeds(),
+            &sample.user_info_program_id,
+        );
+        sample.company = sample.address(&sample.company_seeds(), &sample.company_info_program_id);
+        sample.jobpost = sample.address(&sample.jobpost_seeds(), &sample.jobpost_info_program_id);
+        sample
+    }
+
+    fn address(&self, seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(seeds, program_id).0
+    }
+
+    fn config_seeds(&self) -> Vec<&[u8]> {
+        vec![CONFIG_STATE_ACCOUNT_PREFIX.as_bytes()]
+    }
+
+    fn applicant_seeds(&self) -> Vec<&[u8]> {
+        vec![
+            APPLICANT_STATE_ACCOUNT_PREFIX.as_bytes(),
+            self.candidate.as_ref(),
+        ]
+    }
+
+    fn owner_applicant_seeds(&self) -> Vec<&[u8]> {
+        vec![
+            APPLICANT_STATE_ACCOUNT_PREFIX.as_bytes(),
+            self.company_owner.as_ref(),
+        ]
+    }
+
+    fn company_seeds(&self) -> Vec<&[u8]> {
+        vec![
+            COMPANY_STATE_ACCOUNT_PREFIX.as_bytes(),
+            self.company_seq_number.as_bytes(),
+            self.owner_applicant.as_ref(),
+        ]
+    }
+
+    fn jobpost_seeds(&self) -> Vec<&[u8]> {
+        vec![
+            JOBPOST_STATE_ACCOUNT_PREFIX.as_bytes(),
+            self.job_number.as_bytes(),
+            self.company.as_ref(),
+        ]
+    }
+
+    fn workflow_seeds(&self) -> Vec<&[u8]> {
+        vec![
+            WORKFLOW_STATE_ACCOUNT_PREFIX.as_bytes(),
+            self.jobpost.as_ref(),
+            self.applicant.as_ref(),
+        ]
+    }
+}
+
+fn verify(
+    key: &Pubkey,
+    seeds: &[&[u8]],
+    bump: u8,
+    program_id: &Pubkey,
+) -> Result<(), ProgramError> {
+    let owner = *program_id;
+    let mut lamports = 0;
+    let mut data = [];
+    let account = AccountInfo::new(
+        key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &owner,
+        false,
+        0,
+    );
+
+    verify_pda(
+        &account,
+        seeds,
+        bump,
+        program_id,
+        WorkflowError::WorkflowPdaMismatch,
+    )
+}
+
+/// The canonical bump verifies the canonical address, any other bump or address is rejected.
+fn assert_verified_by_canonical_bump_only(seeds: &[&[u8]], program_id: &Pubkey) {
+    let (key, bump) = Pubkey::find_program_address(seeds, program_id);
+    let mismatch = Err(ProgramError::from(WorkflowError::WorkflowPdaMismatch));
+
+    assert_eq!(verify(&key, seeds, bump, program_id), Ok(()));
+    assert_eq!(
+        verify(&key, seeds, bump.wrapping_sub(1), program_id),
+        mismatch
+    );
+    assert_eq!(verify(&key, seeds, bump, &Pubkey::new_unique()), mismatch);
+    assert_eq!(
+        verify(&Pubkey::new_unique(), seeds, bump, program_id),
+        mismatch
+    );
+}
+
+/// Units `find_program_address` spends on the hashes it computes before it returns.
+fn search_units(seeds: &[&[u8]], program_id: &Pubkey) -> u64 {
+    let (_pda, bump) = Pubkey::find_program_address(seeds, program_id);
+    (256 - bump as u64) * CREATE_PROGRAM_ADDRESS_UNITS
+}
+
+#[test]
+fn stored_bumps_verify_the_workflow_pdas() {
+    for i in 0..SAMPLES {
+        let sample = Sample::new(i);
+
+        //PDAs checked by an UpdateWorkflowState signed by the company owner
+        let checks = [
+            (sample.config_seeds(), &sample.program_id),
+            (sample.company_seeds(), &sample.company_info_program_id),
+            (sample.jobpost_seeds(), &sample.jobpost_info_program_id),
+            (sample.applicant_seeds(), &sample.user_info_program_id),
+            (sample.workflow_seeds(), &sample.program_id),
+            (sample.owner_applicant_seeds(), &sample.user_info_program_id), //applicant info state account of the signer
+        ];
+
+        for (seeds, owner) in checks {
+            assert_verified_by_canonical_bump_only(&seeds, owner);
+        }
+    }
+}
+
+#[test]
+fn stored_bumps_cut_the_compute_units_of_the_baseline_pda_derivations() {
+    let mut search = [0; 3];
+    let mut stored_bump = [0; 3];
+
+    for i in 0..SAMPLES {
+        let sample = Sample::new(i);
+        let applicant = (sample.applicant_seeds(), &sample.user_info_program_id);
+        let jobpost = (sample.jobpost_seeds(), &sample.jobpost_info_program_id);
+        let workflow = (sample.workflow_seeds(), &sample.program_id);
+
+        //PDAs each handler derived with find_program_address before the bumps were stored
+        let handlers = [
+            vec![&applicant, &jobpost, &workflow], //AddWorkflowState
+            vec![&jobpost, &workflow],             //UpdateWorkflowState
+            vec![&applicant, &jobpost, &workflow], //UpdateWorkflowPaymentState
+        ];
+
+        for (handler, derivations) in handlers.iter().enumerate() {
+            for (seeds, owner) in derivations {
+                search[handler] += search_units(seeds, owner);
+                stored_bump[handler] += CREATE_PROGRAM_ADDRESS_UNITS;
+            }
+        }
+    }
+
+    let names = [
+        "AddWorkflowState",
+        "UpdateWorkflowState",
+        "UpdateWorkflowPaymentState",
+    ];
+    for (handler, name) in names.iter().enumerate() {
+        let search_per_call = search[handler] / SAMPLES as u64;
+        let stored_bump_per_call = stored_bump[handler] / SAMPLES as u64;
+        println!(
+            "{}: {} units with find_program_address, {} with stored bumps",
+            name, search_per_call, stored_bump_per_call
+        );
+
+        //A search tries two bumps on average, the stored bump saves at least a third of the units
+        assert!(
+            3 * stored_bump_per_call <= 2 * search_per_call,
+            "{} saves too little: {} against {}",
+            name,
+            stored_bump_per_call,
+            search_per_call
+        );
+    }
+}
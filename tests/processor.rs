@@ -0,0 +1,2367 @@
+//! Add, update, close, migration and payment flows of the workflow program under `solana-program-test`.
+//!
+//! The user, company and job post programs are replaced by a mock program that writes the
+//! given Borsh state at a PDA of its own, so the fixtures are owned and derived like the
+//! accounts of the real sibling programs. The config account is preloaded, InitializeConfig is
+//! tested on its own against a preloaded ProgramData account of the upgradeable loader.
+//! Events are decoded from the `Program data:` entries of the transaction log.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use jobsonchain_workflow_program::{
+    applicant_info_state::ApplicantInfoState,
+    company_info_state::CompanyInfoState,
+    config_state::{ConfigState, PaymentMint, SubscriptionPrices},
+    contants::{
+        APPLICANT_STATE_ACCOUNT_PREFIX, COMPANY_STATE_ACCOUNT_PREFIX, CONFIG_STATE_ACCOUNT_PREFIX,
+        JOBPOST_STATE_ACCOUNT_PREFIX, TREASURY_ACCOUNT_PREFIX, WORKFLOW_STATE_ACCOUNT_PREFIX,
+    },
+    entrypoint::process_instruction,
+    error::WorkflowError,
+    events::{WorkflowEvent, WorkflowKeys},
+    instruction::{
+        self, config_state_address, program_data_address, subscription_state_address, treasury_address, AuthorityType,
+        SiblingPrograms, WorkflowAddresses, WorkflowSeeds, WorkflowStateInstruction,
+    },
+    jobpost_info_state::JobPostState,
+    state::{WorkflowState, WorkflowStateV1},
+    subscription_state::{SubscriptionPlan, SubscriptionState},
+    workflow_status::WorkflowStatus,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    borsh::try_from_slice_unchecked,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program::invoke_signed,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::create_account,
+    system_program,
+    sysvar::Sysvar,
+};
+use solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext};
+use std::sync::Once;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+const COMPANY_SEQ_NUMBER: &str = "1";
+const JOB_NUMBER: &str = "7";
+const WALLET_LAMPORTS: u64 = 100_000_000_000;
+const APPLICATION_PRICE: u64 = 10_000_000;
+
+// Mock sibling program
+
+/// Instruction of the mock sibling program: writes `data` at the PDA of `seeds`,
+/// creating the account with `space` bytes first when it does not belong to the mock yet.
+#[derive(BorshDeserialize, BorshSerialize)]
+struct MockFixture {
+    seeds: Vec<Vec<u8>>,
+    space: u64,
+    data: Vec<u8>,
+}
+
+fn process_mock_instruction(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    let fixture = MockFixture::try_from_slice(instruction_data)?;
+
+    let account_info_iter = &mut accounts.iter();
+    let payer_account = next_account_info(account_info_iter)?;
+    let state_account = next_account_info(account_info_iter)?;
+    let system_program_id = next_account_info(account_info_iter)?;
+
+    if state_account.owner != program_id {
+        let mut seeds: Vec<&[u8]> = fixture.seeds.iter().map(Vec::as_slice).collect();
+        let (_pda, bump) = Pubkey::find_program_address(&seeds, program_id);
+        let bump_seed = [bump];
+        seeds.push(&bump_seed);
+
+        invoke_signed(
+            &create_account(
+                payer_account.key,
+                state_account.key,
+                Rent::get()?.minimum_balance(fixture.space as usize),
+                fixture.space,
+                program_id,
+            ),
+            &[payer_account.clone(), state_account.clone(), system_program_id.clone()],
+            &[&seeds],
+        )?;
+    }
+
+    let mut data = state_account.data.borrow_mut();
+    data.fill(0);
+    data[..fixture.data.len()].copy_from_slice(&fixture.data);
+
+    Ok(())
+}
+
+// Program data logs
+
+/// Syscall stubs of `solana-program-test` with `sol_log_data` written to the transaction log.
+///
+/// Programs run natively by `processor!` only print their `sol_log_data` to stdout, this
+/// writes the `Program data: ` entry the runtime logs, behind the `Program log: ` prefix of
+/// the wrapped `sol_log`.
+struct ProgramDataStubs(Box<dyn SyscallStubs>);
+
+/// Placeholder while the stubs of `solana-program-test` are taken out to be wrapped.
+struct NoStubs;
+impl SyscallStubs for NoStubs {}
+
+impl SyscallStubs for ProgramDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(base64::encode).collect();
+        self.0.sol_log(&format!("Program data: {}", fields.join(" ")))
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+}
+
+/// Starts the bank. The first start installs the stubs of `solana-program-test`, which are then
+/// wrapped once, before any test of the binary processes a transaction.
+async fn start_context(program_test: ProgramTest) -> ProgramTestContext {
+    static WRAP_STUBS: Once = Once::new();
+
+    let context = program_test.start_with_context().await;
+    WRAP_STUBS.call_once(|| {
+        let stubs = set_syscall_stubs(Box::new(NoStubs));
+        set_syscall_stubs(Box::new(ProgramDataStubs(stubs)));
+    });
+    context
+}
+
+/// Workflow events of the `Program data: ` entries in the log of a successful transaction.
+async fn process_with_events(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Vec<WorkflowEvent> {
+    let transaction = signed_transaction(context, instruction, signers);
+    let processed = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    processed.result.unwrap();
+
+    processed
+        .metadata
+        .unwrap()
+        .log_messages
+        .iter()
+        .filter_map(|entry| entry.strip_prefix("Program log: ").unwrap_or(entry).strip_prefix("Program data: "))
+        .map(|data| WorkflowEvent::try_from_slice(&base64::decode(data).unwrap()).unwrap())
+        .collect()
+}
+
+// Fixtures
+
+fn applicant_info_state(owner_pubkey: Pubkey, user_type: &str) -> ApplicantInfoState {
+    ApplicantInfoState {
+        is_initialized: true,
+        owner_pubkey,
+        created_at: 0,
+        updated_at: 0,
+        username: String::new(),
+        name: String::new(),
+        address: String::new(),
+        image_uri: String::new(),
+        bio: String::new(),
+        skills: Vec::new(),
+        designation: String::new(),
+        current_employment_status: String::new(),
+        can_join_in: String::new(),
+        user_type: user_type.to_string(),
+        is_company_profile_complete: false,
+        is_overview_complete: false,
+        is_projects_complete: false,
+        is_contact_info_complete: false,
+        is_education_complete: false,
+        is_work_experience_complete: false,
+    }
+}
+
+fn company_info_state(user_info_state_account_pubkey: Pubkey) -> CompanyInfoState {
+    CompanyInfoState {
+        is_initialized: true,
+        archived: false,
+        user_info_state_account_pubkey,
+        created_at: 0,
+        updated_at: 0,
+        username: String::new(),
+        name: "Jobs On Chain".to_string(),
+        logo_uri: String::new(),
+        domain: String::new(),
+        company_type: String::new(),
+        company_size: String::new(),
+        company_stage: String::new(),
+        funding_amount: String::new(),
+        funding_currency: String::new(),
+        image_uri: String::new(),
+        cover_image_uri: String::new(),
+        founded_in: String::new(),
+        employee_size: String::new(),
+        address: String::new(),
+        description: String::new(),
+        website: String::new(),
+        linkedin: String::new(),
+        twitter: String::new(),
+        facebook: String::new(),
+        instagram: String::new(),
+        subscription_plan: "paynuse".to_string(),
+        subscription_purchased_on: 0,
+        subscription_valid_till: 0,
+        company_seq_number: COMPANY_SEQ_NUMBER.to_string(),
+    }
+}
+
+fn jobpost_state(owner_pubkey: Pubkey, company_pubkey: Pubkey) -> JobPostState {
+    JobPostState {
+        is_initialized: true,
+        archived: false,
+        owner_pubkey,
+        company_pubkey,
+        created_at: 0,
+        updated_at: 0,
+        job_title: "Rust Developer".to_string(),
+        short_description: String::new(),
+        long_description: String::new(),
+        category: vec!["Backend Developer".to_string()],
+        job_type: "full-time".to_string(),
+        currency_type: String::new(),
+        currency: String::new(),
+        min_salary: 0,
+        max_salary: 0,
+        experience_in_months: 0,
+        skills: vec!["Rust".to_string(), "Solana".to_string()],
+        qualification: String::new(),
+        job_location_type: String::new(),
+        country: String::new(),
+        city: String::new(),
+        job_number: JOB_NUMBER.to_string(),
+    }
+}
+
+/// State the mock programs write for the sibling accounts of the workflow.
+struct Siblings {
+    applicant: ApplicantInfoState,
+    company_owner_applicant: ApplicantInfoState,
+    company: CompanyInfoState,
+    jobpost: JobPostState,
+}
+
+struct TestEnv {
+    context: ProgramTestContext,
+    program_id: Pubkey,
+    programs: SiblingPrograms,
+    admin: Keypair,
+    payment_authority: Keypair,
+    candidate: Keypair,
+    company_owner: Keypair,
+    stranger: Keypair,
+}
+
+impl TestEnv {
+    async fn start() -> Self {
+        Self::start_with(|_, _, _| {}).await
+    }
+
+    /// `preload` may add accounts at the derived workflow addresses before the bank starts.
+    async fn start_with(preload: impl FnOnce(&mut ProgramTest, &Pubkey, &WorkflowAddresses)) -> Self {
+        let program_id = Pubkey::new_unique();
+        let programs = SiblingPrograms {
+            user_info_program_id: Pubkey::new_unique(),
+            company_info_program_id: Pubkey::new_unique(),
+            jobpost_info_program_id: Pubkey::new_unique(),
+        };
+        let admin = Keypair::new();
+        let payment_authority = Keypair::new();
+        let candidate = Keypair::new();
+        let company_owner = Keypair::new();
+        let stranger = Keypair::new();
+
+        let mut program_test = ProgramTest::new(
+            "jobsonchain_workflow_program",
+            program_id,
+            processor!(process_instruction),
+        );
+        program_test.add_program("user_info_program", programs.user_info_program_id, processor!(process_mock_instruction));
+        program_test.add_program("company_info_program", programs.company_info_program_id, processor!(process_mock_instruction));
+        program_test.add_program("jobpost_info_program", programs.jobpost_info_program_id, processor!(process_mock_instruction));
+
+        for wallet in [&admin, &payment_authority, &candidate, &company_owner, &stranger] {
+            program_test.add_account(
+                wallet.pubkey(),
+                Account {
+                    lamports: WALLET_LAMPORTS,
+                    owner: system_program::id(),
+                    ..Account::default()
+                },
+            );
+        }
+
+        let (config_pda, bump) = Pubkey::find_program_address(&[CONFIG_STATE_ACCOUNT_PREFIX.as_bytes()], &program_id);
+        let (_treasury_pda, treasury_bump) = Pubkey::find_program_address(&[TREASURY_ACCOUNT_PREFIX.as_bytes()], &program_id);
+        let config_state_data = ConfigState {
+            is_initialized: true,
+            authority: admin.pubkey(),
+            pending_authority: Pubkey::default(),
+            payment_authority: payment_authority.pubkey(),
+            pending_payment_authority: Pubkey::default(),
+            application_price: APPLICATION_PRICE,
+            payment_mints: Vec::new(),
+            subscription_prices: SubscriptionPrices::default(),
+            user_info_program_id: programs.user_info_program_id,
+            company_info_program_id: programs.company_info_program_id,
+            jobpost_info_program_id: programs.jobpost_info_program_id,
+            allow_saving_archived_jobs: false,
+            bump,
+            treasury_bump,
+        };
+        program_test.add_account(config_pda, program_account(&program_id, config_state_data.try_to_vec().unwrap(), ConfigState::LEN));
+        //funded as InitializeConfig leaves it
+        program_test.add_account(
+            treasury_address(&program_id),
+            Account {
+                lamports: treasury_rent(),
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+
+        let seeds = WorkflowSeeds {
+            candidate: candidate.pubkey(),
+            company_owner: company_owner.pubkey(),
+            company_seq_number: COMPANY_SEQ_NUMBER,
+            job_number: JOB_NUMBER,
+        };
+        preload(&mut program_test, &program_id, &WorkflowAddresses::derive(&program_id, &programs, &seeds));
+
+        TestEnv {
+            context: start_context(program_test).await,
+            program_id,
+            programs,
+            admin,
+            payment_authority,
+            candidate,
+            company_owner,
+            stranger,
+        }
+    }
+
+    fn seeds(&self) -> WorkflowSeeds<'static> {
+        WorkflowSeeds {
+            candidate: self.candidate.pubkey(),
+            company_owner: self.company_owner.pubkey(),
+            company_seq_number: COMPANY_SEQ_NUMBER,
+            job_number: JOB_NUMBER,
+        }
+    }
+
+    fn addresses(&self) -> WorkflowAddresses {
+        WorkflowAddresses::derive(&self.program_id, &self.programs, &self.seeds())
+    }
+
+    fn siblings(&self) -> Siblings {
+        let addresses = self.addresses();
+
+        Siblings {
+            applicant: applicant_info_state(self.candidate.pubkey(), "applicant"),
+            company_owner_applicant: applicant_info_state(self.company_owner.pubkey(), "recruiter"),
+            company: company_info_state(addresses.company_owner_info_state),
+            jobpost: jobpost_state(self.company_owner.pubkey(), addresses.company_info_state),
+        }
+    }
+
+    fn applicant_seeds(wallet: &Pubkey) -> Vec<Vec<u8>> {
+        vec![APPLICANT_STATE_ACCOUNT_PREFIX.as_bytes().to_vec(), wallet.to_bytes().to_vec()]
+    }
+
+    fn company_seeds(&self) -> Vec<Vec<u8>> {
+        vec![
+            COMPANY_STATE_ACCOUNT_PREFIX.as_bytes().to_vec(),
+            COMPANY_SEQ_NUMBER.as_bytes().to_vec(),
+            self.addresses().company_owner_info_state.to_bytes().to_vec(),
+        ]
+    }
+
+    fn jobpost_seeds(&self) -> Vec<Vec<u8>> {
+        vec![
+            JOBPOST_STATE_ACCOUNT_PREFIX.as_bytes().to_vec(),
+            JOB_NUMBER.as_bytes().to_vec(),
+            self.addresses().company_info_state.to_bytes().to_vec(),
+        ]
+    }
+
+    /// Writes `data` through the mock `program` at its PDA of `seeds`.
+    async fn write_state(&mut self, program: Pubkey, seeds: Vec<Vec<u8>>, space: usize, data: Vec<u8>) -> Pubkey {
+        let seed_slices: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        let (address, _bump) = Pubkey::find_program_address(&seed_slices, &program);
+        let mock_instruction = Instruction {
+            program_id: program,
+            accounts: vec![
+                AccountMeta::new(self.context.payer.pubkey(), true),
+                AccountMeta::new(address, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: MockFixture { seeds, space: space as u64, data }.try_to_vec().unwrap(),
+        };
+
+        process(&mut self.context, mock_instruction, &[]).await.unwrap();
+        address
+    }
+
+    async fn write_siblings(&mut self, siblings: &Siblings) {
+        let user_info_program_id = self.programs.user_info_program_id;
+        let applicant_seeds = Self::applicant_seeds(&self.candidate.pubkey());
+        let company_owner_applicant_seeds = Self::applicant_seeds(&self.company_owner.pubkey());
+        let company_seeds = self.company_seeds();
+        let jobpost_seeds = self.jobpost_seeds();
+
+        self.write_state(user_info_program_id, applicant_seeds, ApplicantInfoState::LEN, siblings.applicant.try_to_vec().unwrap()).await;
+        self.write_state(user_info_program_id, company_owner_applicant_seeds, ApplicantInfoState::LEN, siblings.company_owner_applicant.try_to_vec().unwrap()).await;
+        self.write_state(self.programs.company_info_program_id, company_seeds, CompanyInfoState::LEN, siblings.company.try_to_vec().unwrap()).await;
+        self.write_state(self.programs.jobpost_info_program_id, jobpost_seeds, JobPostState::LEN, siblings.jobpost.try_to_vec().unwrap()).await;
+    }
+
+    /// Environment with valid sibling accounts and no workflow yet.
+    async fn with_siblings() -> Self {
+        let mut env = Self::start().await;
+        let siblings = env.siblings();
+        env.write_siblings(&siblings).await;
+        env
+    }
+
+    /// Environment with a workflow created by the candidate in `status`.
+    async fn with_workflow(status: WorkflowStatus) -> Self {
+        let mut env = Self::with_siblings().await;
+        let add = env.add(status);
+        process(&mut env.context, add, &[&env.candidate]).await.unwrap();
+        env
+    }
+
+    fn add(&self, status: WorkflowStatus) -> Instruction {
+        instruction::add_workflow_state(&self.program_id, &self.programs, &self.seeds(), status)
+    }
+
+    fn update(&self, signer: &Keypair, status: WorkflowStatus) -> Instruction {
+        self.update_flags(signer, status, status.is_final(), status == WorkflowStatus::Saved)
+    }
+
+    fn update_flags(&self, signer: &Keypair, status: WorkflowStatus, archived: bool, is_saved: bool) -> Instruction {
+        instruction::update_workflow_state(
+            &self.program_id,
+            &self.programs,
+            &self.seeds(),
+            &signer.pubkey(),
+            archived,
+            is_saved,
+            status,
+        )
+    }
+
+    fn update_payment(&self, signer: &Keypair, paid_amount: u64) -> Instruction {
+        instruction::update_workflow_payment_state(
+            &self.program_id,
+            &self.programs,
+            &self.seeds(),
+            &signer.pubkey(),
+            &self.candidate.pubkey(),
+            true,
+            paid_amount,
+        )
+    }
+
+    fn close(&self, signer: &Keypair, recipient: &Pubkey) -> Instruction {
+        instruction::close_workflow_state(&self.program_id, &self.programs, &self.seeds(), &signer.pubkey(), recipient)
+    }
+
+    fn migrate(&self, payer: &Keypair) -> Instruction {
+        instruction::migrate_workflow_state(&self.program_id, &payer.pubkey(), &self.addresses().workflow_state)
+    }
+
+    fn pay(&self, amount: u64) -> Instruction {
+        instruction::pay_for_application(&self.program_id, &self.programs, &self.seeds(), amount)
+    }
+
+    /// Company buys a six months subscription priced at `APPLICATION_PRICE`.
+    async fn subscribe(&mut self) {
+        let prices = SubscriptionPrices {
+            six_months: APPLICATION_PRICE,
+            ..SubscriptionPrices::default()
+        };
+        self.set_subscription_prices(prices).await;
+
+        let purchase = self.purchase(SubscriptionPlan::SixMonths);
+        process(&mut self.context, purchase, &[&self.company_owner]).await.unwrap();
+    }
+
+    async fn set_subscription_prices(&mut self, prices: SubscriptionPrices) {
+        let set_prices = instruction::set_subscription_prices(&self.program_id, &self.admin.pubkey(), prices);
+        process(&mut self.context, set_prices, &[&self.admin]).await.unwrap();
+    }
+
+    fn purchase(&self, plan: SubscriptionPlan) -> Instruction {
+        instruction::purchase_subscription(
+            &self.program_id,
+            &self.programs,
+            &self.company_owner.pubkey(),
+            COMPANY_SEQ_NUMBER,
+            plan,
+        )
+    }
+
+    fn import_legacy_subscription(&self, signer: &Keypair) -> Instruction {
+        instruction::import_legacy_subscription(
+            &self.program_id,
+            &self.programs,
+            &signer.pubkey(),
+            &self.company_owner.pubkey(),
+            COMPANY_SEQ_NUMBER,
+        )
+    }
+
+    async fn subscription_state(&mut self) -> SubscriptionState {
+        let address = subscription_state_address(&self.program_id, &self.addresses().company_info_state);
+        let account = self.account(&address).await.unwrap();
+        assert_eq!(account.owner, self.program_id);
+        assert!(account.lamports >= Rent::default().minimum_balance(SubscriptionState::LEN));
+        try_from_slice_unchecked::<SubscriptionState>(&account.data).unwrap()
+    }
+
+    async fn config_state(&mut self) -> ConfigState {
+        let account = self.account(&config_state_address(&self.program_id)).await.unwrap();
+        try_from_slice_unchecked::<ConfigState>(&account.data).unwrap()
+    }
+
+    async fn workflow_state(&mut self) -> WorkflowState {
+        let account = self.account(&self.addresses().workflow_state).await.unwrap();
+        assert_eq!(account.owner, self.program_id);
+        try_from_slice_unchecked::<WorkflowState>(&account.data).unwrap()
+    }
+
+    async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context.banks_client.get_account(*address).await.unwrap()
+    }
+
+    async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.account(address).await.map_or(0, |account| account.lamports)
+    }
+}
+
+/// System account holding a few lamports, as left by a transfer to a PDA before its creation.
+fn funded_address() -> Account {
+    Account {
+        lamports: 1_000,
+        owner: system_program::id(),
+        ..Account::default()
+    }
+}
+
+/// Rent exempt minimum the treasury PDA keeps.
+fn treasury_rent() -> u64 {
+    Rent::default().minimum_balance(0)
+}
+
+fn program_account(owner: &Pubkey, mut data: Vec<u8>, space: usize) -> Account {
+    data.resize(space, 0);
+
+    Account {
+        lamports: Rent::default().minimum_balance(space),
+        data,
+        owner: *owner,
+        ..Account::default()
+    }
+}
+
+fn signed_transaction(context: &ProgramTestContext, instruction: Instruction, signers: &[&Keypair]) -> Transaction {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    )
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let transaction = signed_transaction(context, instruction, signers);
+
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|error| error.unwrap())
+}
+
+fn assert_workflow_error(result: Result<(), TransactionError>, error: WorkflowError) {
+    assert_eq!(
+        result,
+        Err(TransactionError::InstructionError(0, InstructionError::Custom(error as u32)))
+    );
+}
+
+/// Instruction data of `instruction` with its sibling bumps replaced by `update`.
+fn with_bumps(mut instruction: Instruction, update: impl FnOnce(&mut instruction::SiblingBumps)) -> Instruction {
+    let mut data = WorkflowStateInstruction::try_from_slice(&instruction.data).unwrap();
+
+    match &mut data {
+        WorkflowStateInstruction::AddWorkflowState { bumps, .. }
+        | WorkflowStateInstruction::UpdateWorkflowState { bumps, .. }
+        | WorkflowStateInstruction::UpdateWorkflowPaymentState { bumps, .. }
+        | WorkflowStateInstruction::CloseWorkflowState { bumps }
+        | WorkflowStateInstruction::PayForApplication { bumps, .. } => update(bumps),
+        _ => panic!("instruction carries no sibling bumps"),
+    }
+
+    instruction.data = data.pack();
+    instruction
+}
+
+/// Valid workflow state of the preloaded workflow account, created by the candidate as 'applied'.
+fn preloaded_workflow_state(program_id: &Pubkey, addresses: &WorkflowAddresses) -> WorkflowState {
+    let (_workflow_pda, bump) = Pubkey::find_program_address(
+        &[
+            WORKFLOW_STATE_ACCOUNT_PREFIX.as_bytes(),
+            addresses.jobpost_info_state.as_ref(),
+            addresses.applicant_info_state.as_ref(),
+        ],
+        program_id,
+    );
+
+    WorkflowState {
+        discriminator: WorkflowState::DISCRIMINATOR,
+        version: WorkflowState::VERSION,
+        is_initialized: true,
+        status: WorkflowStatus::Applied,
+        bump,
+        company_owner_pubkey: addresses.company_owner_info_state,
+        company_pubkey: addresses.company_info_state,
+        user_pubkey: addresses.applicant_info_state,
+        job_pubkey: addresses.jobpost_info_state,
+        ..WorkflowState::default()
+    }
+}
+
+/// Environment with valid sibling accounts and a workflow account holding `data`.
+async fn with_preloaded_workflow(data: impl FnOnce(WorkflowState) -> Vec<u8>) -> TestEnv {
+    let mut env = TestEnv::start_with(|program_test, program_id, addresses| {
+        let state = preloaded_workflow_state(program_id, addresses);
+        program_test.add_account(addresses.workflow_state, program_account(program_id, data(state), WorkflowState::LEN));
+    })
+    .await;
+    let siblings = env.siblings();
+    env.write_siblings(&siblings).await;
+    env
+}
+
+// InitializeConfig
+
+/// Bank with only the workflow program, deployed with `upgrade_authority` in its ProgramData account.
+async fn start_uninitialized(upgrade_authority: &Pubkey, signers: &[&Keypair]) -> (ProgramTestContext, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new("jobsonchain_workflow_program", program_id, processor!(process_instruction));
+
+    let program_data = UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(*upgrade_authority),
+    };
+    program_test.add_account(
+        program_data_address(&program_id),
+        Account {
+            lamports: WALLET_LAMPORTS,
+            data: bincode::serialize(&program_data).unwrap(),
+            owner: bpf_loader_upgradeable::id(),
+            ..Account::default()
+        },
+    );
+    for signer in signers {
+        program_test.add_account(
+            signer.pubkey(),
+            Account {
+                lamports: WALLET_LAMPORTS,
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+    }
+
+    (start_context(program_test).await, program_id)
+}
+
+fn initialize(program_id: &Pubkey, initializer: &Pubkey) -> Instruction {
+    let programs = SiblingPrograms {
+        user_info_program_id: Pubkey::new_unique(),
+        company_info_program_id: Pubkey::new_unique(),
+        jobpost_info_program_id: Pubkey::new_unique(),
+    };
+    instruction::initialize_config(program_id, initializer, &Pubkey::new_unique(), &programs)
+}
+
+#[tokio::test]
+async fn initialize_config_makes_the_upgrade_authority_admin() {
+    let upgrade_authority = Keypair::new();
+    let (mut context, program_id) = start_uninitialized(&upgrade_authority.pubkey(), &[&upgrade_authority]).await;
+
+    let init = initialize(&program_id, &upgrade_authority.pubkey());
+    process(&mut context, init, &[&upgrade_authority]).await.unwrap();
+
+    let account = context.banks_client.get_account(config_state_address(&program_id)).await.unwrap().unwrap();
+    let config_state_data = try_from_slice_unchecked::<ConfigState>(&account.data).unwrap();
+    assert_eq!(config_state_data.authority, upgrade_authority.pubkey());
+
+    let treasury = context.banks_client.get_account(treasury_address(&program_id)).await.unwrap().unwrap();
+    assert_eq!(treasury.lamports, treasury_rent());
+    assert_eq!(treasury.owner, system_program::id());
+}
+
+#[tokio::test]
+async fn initialize_config_rejects_a_signer_other_than_the_upgrade_authority() {
+    let stranger = Keypair::new();
+    let (mut context, program_id) = start_uninitialized(&Pubkey::new_unique(), &[&stranger]).await;
+
+    let init = initialize(&program_id, &stranger.pubkey());
+    assert_workflow_error(process(&mut context, init, &[&stranger]).await, WorkflowError::UnauthorizedConfigInitializer);
+}
+
+#[tokio::test]
+async fn initialize_config_rejects_program_data_of_another_program() {
+    let upgrade_authority = Keypair::new();
+    let (mut context, program_id) = start_uninitialized(&upgrade_authority.pubkey(), &[&upgrade_authority]).await;
+
+    let mut init = initialize(&program_id, &upgrade_authority.pubkey());
+    init.accounts[3].pubkey = program_data_address(&Pubkey::new_unique());
+    assert_workflow_error(process(&mut context, init, &[&upgrade_authority]).await, WorkflowError::InvalidProgramDataAccount);
+}
+
+#[tokio::test]
+async fn initialize_config_rejects_an_initialized_config() {
+    let upgrade_authority = Keypair::new();
+    let (mut context, program_id) = start_uninitialized(&upgrade_authority.pubkey(), &[&upgrade_authority]).await;
+
+    let init = initialize(&program_id, &upgrade_authority.pubkey());
+    process(&mut context, init, &[&upgrade_authority]).await.unwrap();
+
+    let init = initialize(&program_id, &upgrade_authority.pubkey());
+    assert_workflow_error(process(&mut context, init, &[&upgrade_authority]).await, WorkflowError::ConfigAlreadyInitialized);
+}
+
+#[tokio::test]
+async fn initialize_config_rejects_an_account_off_the_config_pda() {
+    let upgrade_authority = Keypair::new();
+    let (mut context, program_id) = start_uninitialized(&upgrade_authority.pubkey(), &[&upgrade_authority]).await;
+
+    let mut init = initialize(&program_id, &upgrade_authority.pubkey());
+    init.accounts[1].pubkey = Pubkey::new_unique();
+    assert_workflow_error(process(&mut context, init, &[&upgrade_authority]).await, WorkflowError::ConfigPdaMismatch);
+}
+
+// SetAuthority and AcceptAuthority
+
+#[tokio::test]
+async fn payment_authority_is_transferred_in_two_steps() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let new_payment_authority = Keypair::new();
+
+    let set = instruction::set_authority(&env.program_id, &env.admin.pubkey(), AuthorityType::Payment, &new_payment_authority.pubkey());
+    process(&mut env.context, set, &[&env.admin]).await.unwrap();
+    assert_eq!(env.config_state().await.payment_authority, env.payment_authority.pubkey());
+
+    let accept = instruction::accept_authority(&env.program_id, &env.stranger.pubkey(), AuthorityType::Payment);
+    assert_workflow_error(process(&mut env.context, accept, &[&env.stranger]).await, WorkflowError::PendingAuthorityMismatch);
+
+    let accept = instruction::accept_authority(&env.program_id, &new_payment_authority.pubkey(), AuthorityType::Payment);
+    process(&mut env.context, accept, &[&new_payment_authority]).await.unwrap();
+
+    let config_state_data = env.config_state().await;
+    assert_eq!(config_state_data.payment_authority, new_payment_authority.pubkey());
+    assert_eq!(config_state_data.pending_payment_authority, Pubkey::default());
+    assert_eq!(config_state_data.authority, env.admin.pubkey());
+
+    let record = env.update_payment(&env.payment_authority, APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, record, &[&env.payment_authority]).await, WorkflowError::UnauthorizedPaymentModifier);
+}
+
+#[tokio::test]
+async fn accept_authority_rejects_the_pending_authority_of_the_other_type() {
+    let mut env = TestEnv::start().await;
+    let new_admin = Keypair::new();
+
+    let set = instruction::set_authority(&env.program_id, &env.admin.pubkey(), AuthorityType::Admin, &new_admin.pubkey());
+    process(&mut env.context, set, &[&env.admin]).await.unwrap();
+
+    let accept = instruction::accept_authority(&env.program_id, &new_admin.pubkey(), AuthorityType::Payment);
+    assert_workflow_error(process(&mut env.context, accept, &[&new_admin]).await, WorkflowError::PendingAuthorityMismatch);
+
+    let accept = instruction::accept_authority(&env.program_id, &new_admin.pubkey(), AuthorityType::Admin);
+    process(&mut env.context, accept, &[&new_admin]).await.unwrap();
+    assert_eq!(env.config_state().await.authority, new_admin.pubkey());
+}
+
+// Config setters
+
+#[tokio::test]
+async fn config_setters_reject_a_signer_other_than_the_admin() {
+    let mut env = TestEnv::start().await;
+    let stranger = env.stranger.pubkey();
+    let payment_mint = PaymentMint {
+        mint: Pubkey::new_unique(),
+        application_price: APPLICATION_PRICE,
+    };
+
+    let setters = [
+        instruction::set_payment_mints(&env.program_id, &stranger, vec![payment_mint]),
+        instruction::set_subscription_prices(&env.program_id, &stranger, SubscriptionPrices::default()),
+        instruction::set_sibling_programs(&env.program_id, &stranger, &env.programs),
+        instruction::set_allow_saving_archived_jobs(&env.program_id, &stranger, true),
+    ];
+    for setter in setters {
+        assert_workflow_error(process(&mut env.context, setter, &[&env.stranger]).await, WorkflowError::UnauthorizedConfigAuthority);
+    }
+
+    let config_state_data = env.config_state().await;
+    assert!(config_state_data.payment_mints.is_empty());
+    assert!(!config_state_data.allow_saving_archived_jobs);
+}
+
+#[tokio::test]
+async fn set_payment_mints_rejects_more_than_the_maximum() {
+    let mut env = TestEnv::start().await;
+    let payment_mints = (0..=ConfigState::MAX_PAYMENT_MINTS)
+        .map(|_| PaymentMint {
+            mint: Pubkey::new_unique(),
+            application_price: APPLICATION_PRICE,
+        })
+        .collect();
+
+    let set = instruction::set_payment_mints(&env.program_id, &env.admin.pubkey(), payment_mints);
+    assert_workflow_error(process(&mut env.context, set, &[&env.admin]).await, WorkflowError::TooManyPaymentMints);
+}
+
+// AddWorkflowState
+
+#[tokio::test]
+async fn add_workflow_state_creates_the_workflow() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let addresses = env.addresses();
+
+    let workflow_state_data = env.workflow_state().await;
+    assert!(workflow_state_data.is_initialized);
+    assert_eq!(workflow_state_data.version, WorkflowState::VERSION);
+    assert_eq!(workflow_state_data.status, WorkflowStatus::Applied);
+    assert!(!workflow_state_data.is_saved);
+    assert!(!workflow_state_data.is_paid);
+    assert_eq!(workflow_state_data.user_pubkey, addresses.applicant_info_state);
+    assert_eq!(workflow_state_data.company_owner_pubkey, addresses.company_owner_info_state);
+    assert_eq!(workflow_state_data.company_pubkey, addresses.company_info_state);
+    assert_eq!(workflow_state_data.job_pubkey, addresses.jobpost_info_state);
+    assert_eq!(workflow_state_data.history.len(), 1);
+    assert_eq!(workflow_state_data.history[0].actor, env.candidate.pubkey());
+}
+
+#[tokio::test]
+async fn add_workflow_state_is_paid_by_an_active_subscription() {
+    let mut env = TestEnv::with_siblings().await;
+    env.subscribe().await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    process(&mut env.context, add, &[&env.candidate]).await.unwrap();
+
+    let workflow_state_data = env.workflow_state().await;
+    assert!(workflow_state_data.is_paid);
+    assert_eq!(workflow_state_data.paid_amount, 0);
+}
+
+#[tokio::test]
+async fn add_workflow_state_creates_a_workflow_at_a_funded_address() {
+    let mut env = TestEnv::start_with(|program_test, _, addresses| {
+        program_test.add_account(addresses.workflow_state, funded_address());
+    })
+    .await;
+    let siblings = env.siblings();
+    env.write_siblings(&siblings).await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    process(&mut env.context, add, &[&env.candidate]).await.unwrap();
+
+    assert_eq!(env.workflow_state().await.status, WorkflowStatus::Applied);
+    let rent = Rent::default().minimum_balance(WorkflowState::LEN);
+    assert_eq!(env.lamports(&env.addresses().workflow_state).await, rent);
+}
+
+#[tokio::test]
+async fn add_workflow_state_rejects_a_status_past_applied() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let add = env.add(WorkflowStatus::InProgress);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::InvalidInitialStatus);
+}
+
+#[tokio::test]
+async fn add_workflow_state_rejects_an_existing_workflow() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Saved).await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::WorkflowAlreadyInitialized);
+}
+
+#[tokio::test]
+async fn add_workflow_state_rejects_an_unsigned_owner() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let mut add = env.add(WorkflowStatus::Applied);
+    add.accounts[0].is_signer = false;
+    assert_workflow_error(process(&mut env.context, add, &[]).await, WorkflowError::OwnerNotSigner);
+}
+
+#[tokio::test]
+async fn add_workflow_state_rejects_a_signer_other_than_the_candidate() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let mut add = env.add(WorkflowStatus::Applied);
+    add.accounts[0].pubkey = env.company_owner.pubkey();
+    let add = with_bumps(add, |bumps| bumps.signer_applicant = env.addresses().bumps(&env.seeds(), &env.company_owner.pubkey()).signer_applicant);
+    assert_workflow_error(process(&mut env.context, add, &[&env.company_owner]).await, WorkflowError::ApplicantOwnerMismatch);
+}
+
+#[tokio::test]
+async fn add_workflow_state_rejects_a_workflow_account_off_the_pda() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let mut add = env.add(WorkflowStatus::Applied);
+    add.accounts[4].pubkey = Pubkey::new_unique();
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::WorkflowPdaMismatch);
+}
+
+#[tokio::test]
+async fn add_workflow_state_rejects_an_archived_job_post() {
+    let mut env = TestEnv::start().await;
+    let mut siblings = env.siblings();
+    siblings.jobpost.archived = true;
+    env.write_siblings(&siblings).await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::JobPostArchived);
+}
+
+#[tokio::test]
+async fn add_workflow_state_rejects_an_archived_company() {
+    let mut env = TestEnv::start().await;
+    let mut siblings = env.siblings();
+    siblings.company.archived = true;
+    env.write_siblings(&siblings).await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::CompanyArchived);
+}
+
+#[tokio::test]
+async fn add_workflow_state_saves_archived_jobs_when_allowed() {
+    let mut env = TestEnv::start().await;
+    let mut siblings = env.siblings();
+    siblings.jobpost.archived = true;
+    env.write_siblings(&siblings).await;
+
+    let allow = instruction::set_allow_saving_archived_jobs(&env.program_id, &env.admin.pubkey(), true);
+    process(&mut env.context, allow, &[&env.admin]).await.unwrap();
+
+    let apply = env.add(WorkflowStatus::Applied);
+    assert_workflow_error(process(&mut env.context, apply, &[&env.candidate]).await, WorkflowError::JobPostArchived);
+
+    let save = env.add(WorkflowStatus::Saved);
+    process(&mut env.context, save, &[&env.candidate]).await.unwrap();
+    assert_eq!(env.workflow_state().await.status, WorkflowStatus::Saved);
+}
+
+#[tokio::test]
+async fn add_workflow_state_rejects_a_subscription_account_off_the_pda() {
+    let mut env = TestEnv::with_siblings().await;
+
+    //the config account is owned by the program but is not the subscription PDA of the company
+    let mut add = env.add(WorkflowStatus::Applied);
+    let last = add.accounts.len() - 1;
+    add.accounts[last].pubkey = config_state_address(&env.program_id);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::SubscriptionPdaMismatch);
+}
+
+// Shared account validation, exercised through AddWorkflowState
+
+#[tokio::test]
+async fn workflow_instructions_reject_a_config_not_owned_by_the_program() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let mut add = env.add(WorkflowStatus::Applied);
+    add.accounts[9].pubkey = Pubkey::new_unique();
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::ConfigNotInitialized);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_sibling_programs_not_in_the_config() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let mut add = env.add(WorkflowStatus::Applied);
+    add.accounts.swap(5, 6);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::SiblingProgramMismatch);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_a_company_of_another_program() {
+    let mut env = TestEnv::with_siblings().await;
+    let company_seeds = env.company_seeds();
+    let foreign_company = env.siblings().company.try_to_vec().unwrap();
+    let company = env.write_state(env.programs.jobpost_info_program_id, company_seeds, CompanyInfoState::LEN, foreign_company).await;
+
+    let mut add = env.add(WorkflowStatus::Applied);
+    add.accounts[1].pubkey = company;
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::CompanyNotOwnedBySiblingProgram);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_undecodable_company_data() {
+    let mut env = TestEnv::with_siblings().await;
+    let company_seeds = env.company_seeds();
+    env.write_state(env.programs.company_info_program_id, company_seeds, CompanyInfoState::LEN, vec![2]).await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::InvalidCompanyInfoData);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_an_uninitialized_company() {
+    let mut env = TestEnv::start().await;
+    let mut siblings = env.siblings();
+    siblings.company.is_initialized = false;
+    env.write_siblings(&siblings).await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::CompanyNotInitialized);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_a_company_off_its_pda() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let add = with_bumps(env.add(WorkflowStatus::Applied), |bumps| bumps.company = bumps.company.wrapping_sub(1));
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::CompanyPdaMismatch);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_a_job_post_of_another_program() {
+    let mut env = TestEnv::with_siblings().await;
+    let jobpost_seeds = env.jobpost_seeds();
+    let foreign_jobpost = env.siblings().jobpost.try_to_vec().unwrap();
+    let jobpost = env.write_state(env.programs.company_info_program_id, jobpost_seeds, JobPostState::LEN, foreign_jobpost).await;
+
+    let mut add = env.add(WorkflowStatus::Applied);
+    add.accounts[3].pubkey = jobpost;
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::JobPostNotOwnedBySiblingProgram);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_undecodable_job_post_data() {
+    let mut env = TestEnv::with_siblings().await;
+    let jobpost_seeds = env.jobpost_seeds();
+    env.write_state(env.programs.jobpost_info_program_id, jobpost_seeds, JobPostState::LEN, vec![2]).await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::InvalidJobPostData);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_a_job_post_off_its_pda() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let add = with_bumps(env.add(WorkflowStatus::Applied), |bumps| bumps.jobpost = bumps.jobpost.wrapping_sub(1));
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::JobPostPdaMismatch);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_an_applicant_of_another_program() {
+    let mut env = TestEnv::with_siblings().await;
+    let applicant_seeds = TestEnv::applicant_seeds(&env.candidate.pubkey());
+    let foreign_applicant = env.siblings().applicant.try_to_vec().unwrap();
+    let applicant = env.write_state(env.programs.company_info_program_id, applicant_seeds, ApplicantInfoState::LEN, foreign_applicant).await;
+
+    let mut add = env.add(WorkflowStatus::Applied);
+    add.accounts[2].pubkey = applicant;
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::ApplicantNotOwnedBySiblingProgram);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_undecodable_applicant_data() {
+    let mut env = TestEnv::with_siblings().await;
+    let applicant_seeds = TestEnv::applicant_seeds(&env.candidate.pubkey());
+    env.write_state(env.programs.user_info_program_id, applicant_seeds, ApplicantInfoState::LEN, vec![2]).await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::InvalidApplicantInfoData);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_an_uninitialized_applicant() {
+    let mut env = TestEnv::start().await;
+    let mut siblings = env.siblings();
+    siblings.applicant.is_initialized = false;
+    env.write_siblings(&siblings).await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::ApplicantNotInitialized);
+}
+
+#[tokio::test]
+async fn workflow_instructions_reject_an_applicant_off_its_pda() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let add = with_bumps(env.add(WorkflowStatus::Applied), |bumps| bumps.applicant = bumps.applicant.wrapping_sub(1));
+    assert_workflow_error(process(&mut env.context, add, &[&env.candidate]).await, WorkflowError::ApplicantPdaMismatch);
+}
+
+// UpdateWorkflowState
+
+#[tokio::test]
+async fn update_workflow_state_follows_the_application() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Saved).await;
+
+    let apply = env.update(&env.candidate, WorkflowStatus::Applied);
+    process(&mut env.context, apply, &[&env.candidate]).await.unwrap();
+    let review = env.update(&env.company_owner, WorkflowStatus::InProgress);
+    process(&mut env.context, review, &[&env.company_owner]).await.unwrap();
+    let accept = env.update(&env.company_owner, WorkflowStatus::Accepted);
+    process(&mut env.context, accept, &[&env.company_owner]).await.unwrap();
+
+    let workflow_state_data = env.workflow_state().await;
+    assert_eq!(workflow_state_data.status, WorkflowStatus::Accepted);
+    assert!(workflow_state_data.archived);
+    let history: Vec<_> = workflow_state_data.history.iter().map(|entry| (entry.status, entry.actor)).collect();
+    assert_eq!(
+        history,
+        vec![
+            (WorkflowStatus::Saved, env.candidate.pubkey()),
+            (WorkflowStatus::Applied, env.candidate.pubkey()),
+            (WorkflowStatus::InProgress, env.company_owner.pubkey()),
+            (WorkflowStatus::Accepted, env.company_owner.pubkey()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_an_invalid_transition() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let save = env.update(&env.candidate, WorkflowStatus::Saved);
+    assert_workflow_error(process(&mut env.context, save, &[&env.candidate]).await, WorkflowError::InvalidStatusTransition);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_a_status_of_the_other_side() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let accept = env.update(&env.candidate, WorkflowStatus::Accepted);
+    assert_workflow_error(process(&mut env.context, accept, &[&env.candidate]).await, WorkflowError::UnauthorizedStatusChange);
+
+    let withdraw = env.update(&env.company_owner, WorkflowStatus::Withdraw);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.company_owner]).await, WorkflowError::UnauthorizedStatusChange);
+}
+
+/// Status changes the candidate may make, written out rather than derived from `WorkflowStatus`.
+const CANDIDATE_STATUS_CHANGES: [(WorkflowStatus, WorkflowStatus); 4] = [
+    (WorkflowStatus::Saved, WorkflowStatus::Applied),
+    (WorkflowStatus::Saved, WorkflowStatus::Withdraw),
+    (WorkflowStatus::Applied, WorkflowStatus::Withdraw),
+    (WorkflowStatus::InProgress, WorkflowStatus::Withdraw),
+];
+
+/// Status changes the company owner may make.
+const COMPANY_STATUS_CHANGES: [(WorkflowStatus, WorkflowStatus); 5] = [
+    (WorkflowStatus::Applied, WorkflowStatus::InProgress),
+    (WorkflowStatus::Applied, WorkflowStatus::Accepted),
+    (WorkflowStatus::Applied, WorkflowStatus::Rejected),
+    (WorkflowStatus::InProgress, WorkflowStatus::Accepted),
+    (WorkflowStatus::InProgress, WorkflowStatus::Rejected),
+];
+
+#[tokio::test]
+async fn update_workflow_state_enforces_the_status_changes_of_each_side() {
+    let statuses = [
+        WorkflowStatus::Saved,
+        WorkflowStatus::Applied,
+        WorkflowStatus::InProgress,
+        WorkflowStatus::Accepted,
+        WorkflowStatus::Rejected,
+        WorkflowStatus::Withdraw,
+    ];
+    let mut env = with_workflow_in(WorkflowStatus::Saved).await;
+    let addresses = env.addresses();
+
+    for from in statuses {
+        for to in statuses.into_iter().filter(|to| *to != from) {
+            for company_signs in [false, true] {
+                let (own_changes, other_changes) = if company_signs {
+                    (&COMPANY_STATUS_CHANGES[..], &CANDIDATE_STATUS_CHANGES[..])
+                } else {
+                    (&CANDIDATE_STATUS_CHANGES[..], &COMPANY_STATUS_CHANGES[..])
+                };
+                let expected = if own_changes.contains(&(from, to)) {
+                    Ok(())
+                } else if other_changes.contains(&(from, to)) {
+                    Err(WorkflowError::UnauthorizedStatusChange)
+                } else {
+                    Err(WorkflowError::InvalidStatusTransition)
+                };
+
+                //every case starts from a workflow in `from`, with a fresh blockhash so the
+                //same update sent for another `from` is not a duplicate transaction
+                let state = WorkflowState {
+                    status: from,
+                    ..preloaded_workflow_state(&env.program_id, &addresses)
+                };
+                let account = program_account(&env.program_id, state.try_to_vec().unwrap(), WorkflowState::LEN);
+                env.context.set_account(&addresses.workflow_state, &AccountSharedData::from(account));
+                env.context.get_new_latest_blockhash().await.unwrap();
+
+                let signer = if company_signs { &env.company_owner } else { &env.candidate };
+                let update = env.update(signer, to);
+                let result = process(&mut env.context, update, &[signer]).await;
+
+                let expected = expected.map_err(|error| {
+                    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+                });
+                assert_eq!(result, expected, "{:?} to {:?}, signed by the company: {}", from, to, company_signs);
+                if result.is_ok() {
+                    assert_eq!(env.workflow_state().await.status, to);
+                }
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn update_workflow_state_lets_either_side_archive_without_a_status_change() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Saved).await;
+
+    let archive = env.update_flags(&env.company_owner, WorkflowStatus::Saved, true, true);
+    process(&mut env.context, archive, &[&env.company_owner]).await.unwrap();
+    let unarchive = env.update_flags(&env.candidate, WorkflowStatus::Saved, false, true);
+    process(&mut env.context, unarchive, &[&env.candidate]).await.unwrap();
+
+    let workflow_state_data = env.workflow_state().await;
+    assert_eq!(workflow_state_data.status, WorkflowStatus::Saved);
+    assert!(!workflow_state_data.archived);
+    assert_eq!(workflow_state_data.history.len(), 1);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_the_company_changing_the_saved_flag() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Saved).await;
+
+    let unsave = env.update_flags(&env.company_owner, WorkflowStatus::Saved, false, false);
+    assert_workflow_error(process(&mut env.context, unsave, &[&env.company_owner]).await, WorkflowError::UnauthorizedFlagChange);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_the_company_changing_the_saved_flag_with_the_status() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let reject = env.update_flags(&env.company_owner, WorkflowStatus::Rejected, true, true);
+    assert_workflow_error(process(&mut env.context, reject, &[&env.company_owner]).await, WorkflowError::UnauthorizedFlagChange);
+    assert_eq!(env.workflow_state().await.status, WorkflowStatus::Applied);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_a_stranger() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let withdraw = env.update(&env.stranger, WorkflowStatus::Withdraw);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.stranger]).await, WorkflowError::NotWorkflowParticipant);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_applying_to_an_archived_job_post() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Saved).await;
+    let mut siblings = env.siblings();
+    siblings.jobpost.archived = true;
+    env.write_siblings(&siblings).await;
+
+    let apply = env.update(&env.candidate, WorkflowStatus::Applied);
+    assert_workflow_error(process(&mut env.context, apply, &[&env.candidate]).await, WorkflowError::JobPostArchived);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_a_missing_workflow() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let withdraw = env.update(&env.candidate, WorkflowStatus::Withdraw);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.candidate]).await, WorkflowError::WorkflowNotOwnedByProgram);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_a_legacy_workflow() {
+    let mut env = with_preloaded_workflow(|_| vec![1]).await;
+
+    let withdraw = env.update(&env.candidate, WorkflowStatus::Withdraw);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.candidate]).await, WorkflowError::InvalidWorkflowDiscriminator);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_an_unknown_layout_version() {
+    let mut env = with_preloaded_workflow(|state| {
+        WorkflowState {
+            version: WorkflowState::VERSION + 1,
+            ..state
+        }
+        .try_to_vec()
+        .unwrap()
+    })
+    .await;
+
+    let withdraw = env.update(&env.candidate, WorkflowStatus::Withdraw);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.candidate]).await, WorkflowError::UnsupportedWorkflowVersion);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_undecodable_workflow_data() {
+    let mut env = with_preloaded_workflow(|state| {
+        let mut data = state.try_to_vec().unwrap();
+        data[WorkflowState::STATUS_OFFSET] = 99;
+        data
+    })
+    .await;
+
+    let withdraw = env.update(&env.candidate, WorkflowStatus::Withdraw);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.candidate]).await, WorkflowError::InvalidWorkflowData);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_an_uninitialized_workflow() {
+    let mut env = with_preloaded_workflow(|state| {
+        WorkflowState {
+            is_initialized: false,
+            ..state
+        }
+        .try_to_vec()
+        .unwrap()
+    })
+    .await;
+
+    let withdraw = env.update(&env.candidate, WorkflowStatus::Withdraw);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.candidate]).await, WorkflowError::WorkflowNotInitialized);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_a_workflow_off_its_pda() {
+    let mut env = with_preloaded_workflow(|state| {
+        WorkflowState {
+            bump: state.bump.wrapping_sub(1),
+            ..state
+        }
+        .try_to_vec()
+        .unwrap()
+    })
+    .await;
+
+    let withdraw = env.update(&env.candidate, WorkflowStatus::Withdraw);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.candidate]).await, WorkflowError::WorkflowPdaMismatch);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_a_workflow_of_another_job() {
+    let mut env = with_preloaded_workflow(|state| {
+        WorkflowState {
+            job_pubkey: Pubkey::new_unique(),
+            ..state
+        }
+        .try_to_vec()
+        .unwrap()
+    })
+    .await;
+
+    let withdraw = env.update(&env.candidate, WorkflowStatus::Withdraw);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.candidate]).await, WorkflowError::WorkflowJobMismatch);
+}
+
+#[tokio::test]
+async fn update_workflow_state_rejects_a_workflow_of_another_company_owner() {
+    let mut env = with_preloaded_workflow(|state| {
+        WorkflowState {
+            company_owner_pubkey: Pubkey::new_unique(),
+            ..state
+        }
+        .try_to_vec()
+        .unwrap()
+    })
+    .await;
+
+    let withdraw = env.update(&env.candidate, WorkflowStatus::Withdraw);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.candidate]).await, WorkflowError::WorkflowCompanyOwnerMismatch);
+}
+
+// UpdateWorkflowPaymentState
+
+#[tokio::test]
+async fn update_workflow_payment_state_records_the_payment() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let update_payment = env.update_payment(&env.payment_authority, APPLICATION_PRICE);
+    process(&mut env.context, update_payment, &[&env.payment_authority]).await.unwrap();
+
+    let workflow_state_data = env.workflow_state().await;
+    assert!(workflow_state_data.is_paid);
+    assert_eq!(workflow_state_data.paid_amount, APPLICATION_PRICE);
+    assert_eq!(workflow_state_data.paid_mint, Pubkey::default());
+}
+
+#[tokio::test]
+async fn update_workflow_payment_state_rejects_a_signer_other_than_the_payment_authority() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let update_payment = env.update_payment(&env.company_owner, APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, update_payment, &[&env.company_owner]).await, WorkflowError::UnauthorizedPaymentModifier);
+}
+
+// PayForApplication
+//
+// PaymentNotReceived cannot be reached through the real system and token programs, which always
+// move the full amount: it is covered by the unit test of `validation::verify_received_amount`.
+
+#[tokio::test]
+async fn pay_for_application_moves_lamports_into_the_treasury() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let treasury = treasury_address(&env.program_id);
+
+    let pay = env.pay(APPLICATION_PRICE);
+    process(&mut env.context, pay, &[&env.company_owner]).await.unwrap();
+
+    assert_eq!(env.lamports(&treasury).await, treasury_rent() + APPLICATION_PRICE);
+    let workflow_state_data = env.workflow_state().await;
+    assert!(workflow_state_data.is_paid);
+    assert_eq!(workflow_state_data.paid_amount, APPLICATION_PRICE);
+}
+
+#[tokio::test]
+async fn pay_for_application_rejects_a_zero_amount() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let pay = env.pay(0);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::InvalidPaymentAmount);
+}
+
+#[tokio::test]
+async fn pay_for_application_rejects_an_amount_other_than_the_application_price() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    for amount in [1, APPLICATION_PRICE - 1, APPLICATION_PRICE + 1] {
+        let pay = env.pay(amount);
+        assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::PaymentAmountMismatch);
+    }
+}
+
+#[tokio::test]
+async fn pay_for_application_charges_the_price_set_by_the_admin() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let treasury = treasury_address(&env.program_id);
+
+    let set_price = instruction::set_application_price(&env.program_id, &env.admin.pubkey(), 2 * APPLICATION_PRICE);
+    process(&mut env.context, set_price, &[&env.admin]).await.unwrap();
+
+    let pay = env.pay(APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::PaymentAmountMismatch);
+    let pay = env.pay(2 * APPLICATION_PRICE);
+    process(&mut env.context, pay, &[&env.company_owner]).await.unwrap();
+    assert_eq!(env.lamports(&treasury).await, treasury_rent() + 2 * APPLICATION_PRICE);
+}
+
+#[tokio::test]
+async fn set_application_price_rejects_a_signer_other_than_the_admin() {
+    let mut env = TestEnv::start().await;
+
+    let set_price = instruction::set_application_price(&env.program_id, &env.stranger.pubkey(), 0);
+    assert_workflow_error(process(&mut env.context, set_price, &[&env.stranger]).await, WorkflowError::UnauthorizedConfigAuthority);
+}
+
+#[tokio::test]
+async fn pay_for_application_rejects_the_candidate() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let mut pay = env.pay(APPLICATION_PRICE);
+    pay.accounts[0].pubkey = env.candidate.pubkey();
+    let pay = with_bumps(pay, |bumps| bumps.signer_applicant = bumps.applicant);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.candidate]).await, WorkflowError::UnauthorizedPayer);
+}
+
+#[tokio::test]
+async fn pay_for_application_rejects_a_subscribed_company() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    env.subscribe().await;
+
+    let pay = env.pay(APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::CompanySubscribed);
+}
+
+#[tokio::test]
+async fn pay_for_application_rejects_a_paid_workflow() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let pay = env.pay(APPLICATION_PRICE);
+    process(&mut env.context, pay, &[&env.company_owner]).await.unwrap();
+
+    let pay_again = env.pay(APPLICATION_PRICE + 1);
+    assert_workflow_error(process(&mut env.context, pay_again, &[&env.company_owner]).await, WorkflowError::WorkflowAlreadyPaid);
+}
+
+#[tokio::test]
+async fn pay_for_application_rejects_a_treasury_off_its_pda() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let mut pay = env.pay(APPLICATION_PRICE);
+    let last = pay.accounts.len() - 1;
+    pay.accounts[last].pubkey = env.stranger.pubkey();
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::TreasuryPdaMismatch);
+}
+
+// CloseWorkflowState
+
+/// Environment with a preloaded workflow in `status`.
+async fn with_workflow_in(status: WorkflowStatus) -> TestEnv {
+    with_preloaded_workflow(|state| WorkflowState { status, ..state }.try_to_vec().unwrap()).await
+}
+
+#[tokio::test]
+async fn close_workflow_state_lets_the_candidate_close_saved_and_withdrawn_workflows() {
+    for status in [WorkflowStatus::Saved, WorkflowStatus::Withdraw] {
+        let mut env = with_workflow_in(status).await;
+        let workflow = env.addresses().workflow_state;
+        let rent = env.lamports(&workflow).await;
+        let recipient = env.candidate.pubkey();
+        let balance = env.lamports(&recipient).await;
+
+        let close = env.close(&env.candidate, &recipient);
+        process(&mut env.context, close, &[&env.candidate]).await.unwrap();
+
+        assert_eq!(env.account(&workflow).await, None);
+        assert_eq!(env.lamports(&recipient).await, balance + rent);
+    }
+}
+
+#[tokio::test]
+async fn close_workflow_state_lets_the_company_close_a_rejected_workflow() {
+    let mut env = with_workflow_in(WorkflowStatus::Rejected).await;
+    let workflow = env.addresses().workflow_state;
+    let rent = env.lamports(&workflow).await;
+    let recipient = env.stranger.pubkey();
+    let balance = env.lamports(&recipient).await;
+
+    let close = env.close(&env.company_owner, &recipient);
+    process(&mut env.context, close, &[&env.company_owner]).await.unwrap();
+
+    assert_eq!(env.account(&workflow).await, None);
+    assert_eq!(env.lamports(&recipient).await, balance + rent);
+}
+
+#[tokio::test]
+async fn close_workflow_state_rejects_the_other_side() {
+    let mut env = with_workflow_in(WorkflowStatus::Withdraw).await;
+    let close = env.close(&env.company_owner, &env.company_owner.pubkey());
+    assert_workflow_error(process(&mut env.context, close, &[&env.company_owner]).await, WorkflowError::UnauthorizedClose);
+
+    let mut env = with_workflow_in(WorkflowStatus::Rejected).await;
+    let close = env.close(&env.candidate, &env.candidate.pubkey());
+    assert_workflow_error(process(&mut env.context, close, &[&env.candidate]).await, WorkflowError::UnauthorizedClose);
+}
+
+#[tokio::test]
+async fn close_workflow_state_rejects_an_open_or_accepted_workflow() {
+    for status in [WorkflowStatus::Applied, WorkflowStatus::InProgress, WorkflowStatus::Accepted] {
+        let mut env = with_workflow_in(status).await;
+
+        for signer in [&env.candidate, &env.company_owner] {
+            let close = env.close(signer, &signer.pubkey());
+            assert_workflow_error(process(&mut env.context, close, &[signer]).await, WorkflowError::WorkflowNotClosable);
+        }
+    }
+}
+
+#[tokio::test]
+async fn close_workflow_state_rejects_the_workflow_as_recipient() {
+    let mut env = with_workflow_in(WorkflowStatus::Saved).await;
+
+    let close = env.close(&env.candidate, &env.addresses().workflow_state);
+    assert_workflow_error(process(&mut env.context, close, &[&env.candidate]).await, WorkflowError::InvalidCloseRecipient);
+}
+
+#[tokio::test]
+async fn close_workflow_state_rejects_a_recipient_balance_overflow() {
+    let mut env = with_workflow_in(WorkflowStatus::Saved).await;
+    let recipient = Pubkey::new_unique();
+    let account = Account {
+        lamports: u64::MAX,
+        owner: system_program::id(),
+        ..Account::default()
+    };
+    env.context.set_account(&recipient, &AccountSharedData::from(account));
+
+    let close = env.close(&env.candidate, &recipient);
+    assert_workflow_error(process(&mut env.context, close, &[&env.candidate]).await, WorkflowError::AmountOverflow);
+    assert!(env.workflow_state().await.is_initialized);
+}
+
+// PayForApplicationWithToken
+
+/// Token accounts of a payment in an allowed mint.
+struct TokenPayment {
+    mint: Pubkey,
+    source: Pubkey,
+    treasury: Pubkey,
+}
+
+impl TestEnv {
+    async fn create_token_account(&mut self, account: &Keypair, mint: &Pubkey, owner: &Pubkey) {
+        let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+        let create = create_account(
+            &self.context.payer.pubkey(),
+            &account.pubkey(),
+            rent,
+            spl_token::state::Account::LEN as u64,
+            &spl_token::id(),
+        );
+        process(&mut self.context, create, &[account]).await.unwrap();
+
+        let initialize = spl_token::instruction::initialize_account(&spl_token::id(), &account.pubkey(), mint, owner).unwrap();
+        process(&mut self.context, initialize, &[]).await.unwrap();
+    }
+
+    /// Mint allowed in the config at `APPLICATION_PRICE`, with `amount` tokens in an account of the company owner.
+    async fn token_payment(&mut self, amount: u64) -> TokenPayment {
+        let mint = Keypair::new();
+        let source = Keypair::new();
+        let treasury = Keypair::new();
+        let mint_authority = self.context.payer.pubkey();
+
+        let rent = Rent::default().minimum_balance(spl_token::state::Mint::LEN);
+        let create = create_account(&mint_authority, &mint.pubkey(), rent, spl_token::state::Mint::LEN as u64, &spl_token::id());
+        process(&mut self.context, create, &[&mint]).await.unwrap();
+        let initialize = spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &mint_authority, None, 6).unwrap();
+        process(&mut self.context, initialize, &[]).await.unwrap();
+
+        self.create_token_account(&source, &mint.pubkey(), &self.company_owner.pubkey()).await;
+        self.create_token_account(&treasury, &mint.pubkey(), &treasury_address(&self.program_id)).await;
+
+        let mint_to = spl_token::instruction::mint_to(&spl_token::id(), &mint.pubkey(), &source.pubkey(), &mint_authority, &[], amount).unwrap();
+        process(&mut self.context, mint_to, &[]).await.unwrap();
+
+        let payment_mint = PaymentMint {
+            mint: mint.pubkey(),
+            application_price: APPLICATION_PRICE,
+        };
+        let allow = instruction::set_payment_mints(&self.program_id, &self.admin.pubkey(), vec![payment_mint]);
+        process(&mut self.context, allow, &[&self.admin]).await.unwrap();
+
+        TokenPayment {
+            mint: mint.pubkey(),
+            source: source.pubkey(),
+            treasury: treasury.pubkey(),
+        }
+    }
+
+    fn pay_with_token(&self, payment: &TokenPayment, amount: u64) -> Instruction {
+        instruction::pay_for_application_with_token(
+            &self.program_id,
+            &self.programs,
+            &self.seeds(),
+            &payment.source,
+            &payment.treasury,
+            &payment.mint,
+            amount,
+        )
+    }
+
+    async fn token_amount(&mut self, address: &Pubkey) -> u64 {
+        let account = self.account(address).await.unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+}
+
+#[tokio::test]
+async fn pay_for_application_with_token_moves_tokens_into_the_treasury() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+
+    let pay = env.pay_with_token(&payment, APPLICATION_PRICE);
+    process(&mut env.context, pay, &[&env.company_owner]).await.unwrap();
+
+    assert_eq!(env.token_amount(&payment.treasury).await, APPLICATION_PRICE);
+    assert_eq!(env.token_amount(&payment.source).await, 0);
+    let workflow_state_data = env.workflow_state().await;
+    assert!(workflow_state_data.is_paid);
+    assert_eq!(workflow_state_data.paid_amount, APPLICATION_PRICE);
+    assert_eq!(workflow_state_data.paid_mint, payment.mint);
+}
+
+#[tokio::test]
+async fn pay_for_application_with_token_rejects_a_zero_amount() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+
+    let pay = env.pay_with_token(&payment, 0);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::InvalidPaymentAmount);
+}
+
+#[tokio::test]
+async fn pay_for_application_with_token_rejects_an_amount_other_than_the_mint_price() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(2 * APPLICATION_PRICE).await;
+
+    for amount in [APPLICATION_PRICE - 1, APPLICATION_PRICE + 1] {
+        let pay = env.pay_with_token(&payment, amount);
+        assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::PaymentAmountMismatch);
+    }
+}
+
+#[tokio::test]
+async fn pay_for_application_with_token_rejects_a_subscribed_company() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+    env.subscribe().await;
+
+    let pay = env.pay_with_token(&payment, APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::CompanySubscribed);
+}
+
+#[tokio::test]
+async fn pay_for_application_with_token_rejects_another_token_program() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+
+    let mut pay = env.pay_with_token(&payment, APPLICATION_PRICE);
+    let token_program = pay.accounts.len() - 1;
+    pay.accounts[token_program].pubkey = system_program::id();
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::InvalidTokenProgram);
+}
+
+#[tokio::test]
+async fn pay_for_application_with_token_rejects_a_mint_not_allowed() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+
+    let clear = instruction::set_payment_mints(&env.program_id, &env.admin.pubkey(), Vec::new());
+    process(&mut env.context, clear, &[&env.admin]).await.unwrap();
+
+    let pay = env.pay_with_token(&payment, APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::PaymentMintNotAllowed);
+}
+
+#[tokio::test]
+async fn pay_for_application_with_token_rejects_a_mint_outside_the_token_program() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+
+    let fake_mint = env.stranger.pubkey();
+    let payment_mint = PaymentMint {
+        mint: fake_mint,
+        application_price: APPLICATION_PRICE,
+    };
+    let allow = instruction::set_payment_mints(&env.program_id, &env.admin.pubkey(), vec![payment_mint]);
+    process(&mut env.context, allow, &[&env.admin]).await.unwrap();
+
+    let pay = env.pay_with_token(&TokenPayment { mint: fake_mint, ..payment }, APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::InvalidTokenAccountData);
+}
+
+#[tokio::test]
+async fn pay_for_application_with_token_rejects_a_treasury_account_outside_the_token_program() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+
+    let pay = env.pay_with_token(&TokenPayment { treasury: env.stranger.pubkey(), ..payment }, APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::InvalidTreasuryTokenAccount);
+}
+
+#[tokio::test]
+async fn pay_for_application_with_token_rejects_a_token_account_not_held_by_the_treasury() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+
+    //the source account holds the right mint but belongs to the company owner
+    let pay = env.pay_with_token(&TokenPayment { treasury: payment.source, ..payment }, APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::InvalidTreasuryTokenAccount);
+}
+
+#[tokio::test]
+async fn pay_for_application_with_token_rejects_a_treasury_account_of_another_allowed_mint() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+    let other_payment = env.token_payment(APPLICATION_PRICE).await;
+
+    let payment_mints = [payment.mint, other_payment.mint]
+        .iter()
+        .map(|mint| PaymentMint {
+            mint: *mint,
+            application_price: APPLICATION_PRICE,
+        })
+        .collect();
+    let allow = instruction::set_payment_mints(&env.program_id, &env.admin.pubkey(), payment_mints);
+    process(&mut env.context, allow, &[&env.admin]).await.unwrap();
+
+    //the treasury holds the other mint, a transfer of the paid mint into it would fail anyway
+    let pay = env.pay_with_token(&TokenPayment { treasury: other_payment.treasury, ..payment }, APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, pay, &[&env.company_owner]).await, WorkflowError::InvalidTreasuryTokenAccount);
+}
+
+// WithdrawTreasury and WithdrawTreasuryTokens
+
+#[tokio::test]
+async fn withdraw_treasury_sends_payments_to_the_recipient() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let pay = env.pay(APPLICATION_PRICE);
+    process(&mut env.context, pay, &[&env.company_owner]).await.unwrap();
+    let recipient = env.stranger.pubkey();
+    let balance = env.lamports(&recipient).await;
+
+    let withdraw = instruction::withdraw_treasury(&env.program_id, &env.admin.pubkey(), &recipient, APPLICATION_PRICE);
+    process(&mut env.context, withdraw, &[&env.admin]).await.unwrap();
+
+    assert_eq!(env.lamports(&recipient).await, balance + APPLICATION_PRICE);
+    assert_eq!(env.lamports(&treasury_address(&env.program_id)).await, treasury_rent());
+}
+
+#[tokio::test]
+async fn withdraw_treasury_keeps_the_rent_exempt_minimum() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let pay = env.pay(APPLICATION_PRICE);
+    process(&mut env.context, pay, &[&env.company_owner]).await.unwrap();
+
+    let withdraw = instruction::withdraw_treasury(&env.program_id, &env.admin.pubkey(), &env.stranger.pubkey(), APPLICATION_PRICE + 1);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.admin]).await, WorkflowError::InsufficientTreasuryBalance);
+}
+
+#[tokio::test]
+async fn withdraw_treasury_rejects_a_signer_other_than_the_admin() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let pay = env.pay(APPLICATION_PRICE);
+    process(&mut env.context, pay, &[&env.company_owner]).await.unwrap();
+
+    let withdraw = instruction::withdraw_treasury(&env.program_id, &env.payment_authority.pubkey(), &env.payment_authority.pubkey(), APPLICATION_PRICE);
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.payment_authority]).await, WorkflowError::UnauthorizedConfigAuthority);
+}
+
+#[tokio::test]
+async fn pay_for_application_accepts_a_price_below_the_treasury_rent() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let set_price = instruction::set_application_price(&env.program_id, &env.admin.pubkey(), 1);
+    process(&mut env.context, set_price, &[&env.admin]).await.unwrap();
+
+    let pay = env.pay(1);
+    process(&mut env.context, pay, &[&env.company_owner]).await.unwrap();
+    assert_eq!(env.lamports(&treasury_address(&env.program_id)).await, treasury_rent() + 1);
+}
+
+#[tokio::test]
+async fn withdraw_treasury_tokens_sends_token_payments_to_the_recipient() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+    let pay = env.pay_with_token(&payment, APPLICATION_PRICE);
+    process(&mut env.context, pay, &[&env.company_owner]).await.unwrap();
+    let recipient = Keypair::new();
+    env.create_token_account(&recipient, &payment.mint, &env.stranger.pubkey()).await;
+
+    let withdraw = instruction::withdraw_treasury_tokens(
+        &env.program_id,
+        &env.admin.pubkey(),
+        &payment.treasury,
+        &recipient.pubkey(),
+        &payment.mint,
+        APPLICATION_PRICE,
+    );
+    process(&mut env.context, withdraw, &[&env.admin]).await.unwrap();
+
+    assert_eq!(env.token_amount(&payment.treasury).await, 0);
+    assert_eq!(env.token_amount(&recipient.pubkey()).await, APPLICATION_PRICE);
+}
+
+#[tokio::test]
+async fn withdraw_treasury_tokens_rejects_a_signer_other_than_the_admin() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+    let payment = env.token_payment(APPLICATION_PRICE).await;
+    let pay = env.pay_with_token(&payment, APPLICATION_PRICE);
+    process(&mut env.context, pay, &[&env.company_owner]).await.unwrap();
+
+    let withdraw = instruction::withdraw_treasury_tokens(
+        &env.program_id,
+        &env.company_owner.pubkey(),
+        &payment.treasury,
+        &payment.source,
+        &payment.mint,
+        APPLICATION_PRICE,
+    );
+    assert_workflow_error(process(&mut env.context, withdraw, &[&env.company_owner]).await, WorkflowError::UnauthorizedConfigAuthority);
+}
+
+// PurchaseSubscription and ImportLegacySubscription
+
+#[tokio::test]
+async fn purchase_subscription_creates_a_subscription_at_a_funded_address() {
+    let mut env = TestEnv::start_with(|program_test, program_id, addresses| {
+        let subscription = subscription_state_address(program_id, &addresses.company_info_state);
+        program_test.add_account(subscription, funded_address());
+    })
+    .await;
+    let siblings = env.siblings();
+    env.write_siblings(&siblings).await;
+
+    env.subscribe().await;
+
+    let subscription_state_data = env.subscription_state().await;
+    assert_eq!(subscription_state_data.plan, SubscriptionPlan::SixMonths);
+    assert_eq!(subscription_state_data.paid_amount, APPLICATION_PRICE);
+}
+
+/// Environment where every subscription plan is for sale.
+async fn with_subscription_plans_for_sale() -> TestEnv {
+    let mut env = TestEnv::start().await;
+    let siblings = env.siblings();
+    env.write_siblings(&siblings).await;
+    env.set_subscription_prices(SubscriptionPrices {
+        six_months: APPLICATION_PRICE,
+        yearly: 2 * APPLICATION_PRICE,
+        forever: 3 * APPLICATION_PRICE,
+    })
+    .await;
+    env
+}
+
+#[tokio::test]
+async fn purchase_subscription_rejects_a_purchase_while_forever_is_active() {
+    let mut env = with_subscription_plans_for_sale().await;
+    let purchase = env.purchase(SubscriptionPlan::Forever);
+    process(&mut env.context, purchase, &[&env.company_owner]).await.unwrap();
+    let treasury_lamports = env.lamports(&treasury_address(&env.program_id)).await;
+
+    for plan in [SubscriptionPlan::SixMonths, SubscriptionPlan::Yearly] {
+        let purchase = env.purchase(plan);
+        assert_workflow_error(process(&mut env.context, purchase, &[&env.company_owner]).await, WorkflowError::SubscriptionNeverExpires);
+    }
+
+    let subscription_state_data = env.subscription_state().await;
+    assert_eq!(subscription_state_data.plan, SubscriptionPlan::Forever);
+    assert_eq!(subscription_state_data.valid_till, u64::MAX);
+    assert_eq!(env.lamports(&treasury_address(&env.program_id)).await, treasury_lamports);
+}
+
+#[tokio::test]
+async fn purchase_subscription_keeps_the_longer_plan_on_renewal() {
+    let mut env = with_subscription_plans_for_sale().await;
+    let purchase = env.purchase(SubscriptionPlan::Yearly);
+    process(&mut env.context, purchase, &[&env.company_owner]).await.unwrap();
+    let yearly = env.subscription_state().await;
+
+    let purchase = env.purchase(SubscriptionPlan::SixMonths);
+    process(&mut env.context, purchase, &[&env.company_owner]).await.unwrap();
+
+    let subscription_state_data = env.subscription_state().await;
+    assert_eq!(subscription_state_data.plan, SubscriptionPlan::Yearly);
+    assert_eq!(
+        subscription_state_data.valid_till,
+        yearly.valid_till + SubscriptionPlan::SixMonths.duration_in_millis().unwrap()
+    );
+    assert_eq!(subscription_state_data.paid_amount, APPLICATION_PRICE);
+}
+
+#[tokio::test]
+async fn purchase_subscription_extends_a_renewal_from_valid_till() {
+    let mut env = with_subscription_plans_for_sale().await;
+    let purchase = env.purchase(SubscriptionPlan::SixMonths);
+    process(&mut env.context, purchase, &[&env.company_owner]).await.unwrap();
+    let first = env.subscription_state().await;
+
+    env.context.get_new_latest_blockhash().await.unwrap();
+    let purchase = env.purchase(SubscriptionPlan::SixMonths);
+    process(&mut env.context, purchase, &[&env.company_owner]).await.unwrap();
+
+    let renewed = env.subscription_state().await;
+    assert_eq!(renewed.plan, SubscriptionPlan::SixMonths);
+    assert_eq!(renewed.valid_till, first.valid_till + SubscriptionPlan::SixMonths.duration_in_millis().unwrap());
+}
+
+#[tokio::test]
+async fn purchase_subscription_rejects_a_plan_not_for_sale() {
+    let mut env = TestEnv::with_siblings().await;
+    env.set_subscription_prices(SubscriptionPrices {
+        yearly: APPLICATION_PRICE,
+        ..SubscriptionPrices::default()
+    })
+    .await;
+
+    for plan in [SubscriptionPlan::PayNUse, SubscriptionPlan::SixMonths, SubscriptionPlan::Forever] {
+        let purchase = env.purchase(plan);
+        assert_workflow_error(process(&mut env.context, purchase, &[&env.company_owner]).await, WorkflowError::SubscriptionPlanNotForSale);
+    }
+}
+
+#[tokio::test]
+async fn purchase_subscription_rejects_a_signer_other_than_the_company_owner() {
+    let mut env = with_subscription_plans_for_sale().await;
+
+    let mut purchase = env.purchase(SubscriptionPlan::Yearly);
+    purchase.accounts[0].pubkey = env.stranger.pubkey();
+    assert_workflow_error(process(&mut env.context, purchase, &[&env.stranger]).await, WorkflowError::UnauthorizedSubscriptionPurchaser);
+    assert!(env.account(&subscription_state_address(&env.program_id, &env.addresses().company_info_state)).await.is_none());
+}
+
+/// Environment whose company info state account records the legacy `plan` valid till `valid_till`.
+async fn with_legacy_subscription(plan: &str, valid_till: u64) -> TestEnv {
+    let mut env = TestEnv::start().await;
+    let mut siblings = env.siblings();
+    siblings.company.subscription_plan = plan.to_string();
+    siblings.company.subscription_purchased_on = 1;
+    siblings.company.subscription_valid_till = valid_till;
+    env.write_siblings(&siblings).await;
+    env
+}
+
+#[tokio::test]
+async fn import_legacy_subscription_keeps_applications_paid() {
+    let mut env = with_legacy_subscription("yearly", u64::MAX - 1).await;
+
+    let import = env.import_legacy_subscription(&env.admin);
+    process(&mut env.context, import, &[&env.admin]).await.unwrap();
+
+    let subscription_state_data = env.subscription_state().await;
+    assert_eq!(subscription_state_data.plan, SubscriptionPlan::Yearly);
+    assert_eq!(subscription_state_data.purchased_on, 1);
+    assert_eq!(subscription_state_data.valid_till, u64::MAX - 1);
+    assert_eq!(subscription_state_data.paid_amount, 0);
+
+    let add = env.add(WorkflowStatus::Applied);
+    process(&mut env.context, add, &[&env.candidate]).await.unwrap();
+    assert!(env.workflow_state().await.is_paid);
+}
+
+#[tokio::test]
+async fn import_legacy_subscription_rejects_an_expired_or_pay_per_use_plan() {
+    for (plan, valid_till) in [("yearly", 1), ("paynuse", u64::MAX - 1)] {
+        let mut env = with_legacy_subscription(plan, valid_till).await;
+
+        let import = env.import_legacy_subscription(&env.admin);
+        assert_workflow_error(process(&mut env.context, import, &[&env.admin]).await, WorkflowError::LegacySubscriptionNotActive);
+    }
+}
+
+#[tokio::test]
+async fn import_legacy_subscription_rejects_an_unknown_plan() {
+    let mut env = with_legacy_subscription("lifetime", u64::MAX - 1).await;
+
+    let import = env.import_legacy_subscription(&env.admin);
+    assert_workflow_error(process(&mut env.context, import, &[&env.admin]).await, WorkflowError::UnknownSubscriptionPlan);
+}
+
+#[tokio::test]
+async fn import_legacy_subscription_rejects_a_signer_other_than_the_admin() {
+    let mut env = with_legacy_subscription("forever", u64::MAX - 1).await;
+
+    let import = env.import_legacy_subscription(&env.company_owner);
+    assert_workflow_error(process(&mut env.context, import, &[&env.company_owner]).await, WorkflowError::UnauthorizedConfigAuthority);
+}
+
+#[tokio::test]
+async fn import_legacy_subscription_never_overwrites_a_purchase() {
+    let mut env = with_legacy_subscription("forever", u64::MAX - 1).await;
+    env.subscribe().await;
+
+    let import = env.import_legacy_subscription(&env.admin);
+    assert_workflow_error(process(&mut env.context, import, &[&env.admin]).await, WorkflowError::SubscriptionAlreadyExists);
+    assert_eq!(env.subscription_state().await.plan, SubscriptionPlan::SixMonths);
+}
+
+// MigrateWorkflowState
+
+/// Legacy workflow state of the preloaded workflow account, as written by the baseline AddWorkflowState.
+fn legacy_workflow_state(addresses: &WorkflowAddresses, status: &str) -> WorkflowStateV1 {
+    WorkflowStateV1 {
+        is_initialized: true,
+        archived: false,
+        is_saved: false,
+        company_owner_pubkey: addresses.company_owner_info_state,
+        company_pubkey: addresses.company_info_state,
+        user_pubkey: addresses.applicant_info_state,
+        job_pubkey: addresses.jobpost_info_state,
+        status: status.to_string(),
+        job_applied_at: 1,
+        is_paid: false,
+        paid_amount: 0,
+        paid_at: 0,
+        updated_at: 1,
+    }
+}
+
+/// Environment with valid sibling accounts and a legacy workflow account in `status` at `address`,
+/// the workflow PDA when `None`, owned by `owner`, the workflow program when `None`.
+async fn with_legacy_workflow(status: &'static str, address: Option<Pubkey>, owner: Option<Pubkey>) -> TestEnv {
+    let mut env = TestEnv::start_with(|program_test, program_id, addresses| {
+        let data = legacy_workflow_state(addresses, status).try_to_vec().unwrap();
+        program_test.add_account(
+            address.unwrap_or(addresses.workflow_state),
+            program_account(&owner.unwrap_or(*program_id), data, WorkflowStateV1::LEN),
+        );
+    })
+    .await;
+    let siblings = env.siblings();
+    env.write_siblings(&siblings).await;
+    env
+}
+
+#[tokio::test]
+async fn migrate_workflow_state_rewrites_a_legacy_workflow() {
+    let mut env = with_legacy_workflow("applied", None, None).await;
+    let addresses = env.addresses();
+    let legacy_rent = env.lamports(&addresses.workflow_state).await;
+    let balance = env.lamports(&env.stranger.pubkey()).await;
+
+    let migrate = env.migrate(&env.stranger);
+    process(&mut env.context, migrate, &[&env.stranger]).await.unwrap();
+
+    let expected = legacy_workflow_state(&addresses, "applied").upgrade(preloaded_workflow_state(&env.program_id, &addresses).bump);
+    assert_eq!(env.workflow_state().await, expected);
+    assert_eq!(expected.status, WorkflowStatus::Applied);
+
+    //the payer tops the account up to the rent of the current layout
+    let account = env.account(&addresses.workflow_state).await.unwrap();
+    let rent = Rent::default().minimum_balance(WorkflowState::LEN);
+    assert_eq!(account.data.len(), WorkflowState::LEN);
+    assert_eq!(account.lamports, rent);
+    assert_eq!(env.lamports(&env.stranger.pubkey()).await, balance - (rent - legacy_rent));
+
+    let withdraw = env.update(&env.candidate, WorkflowStatus::Withdraw);
+    process(&mut env.context, withdraw, &[&env.candidate]).await.unwrap();
+    assert_eq!(env.workflow_state().await.status, WorkflowStatus::Withdraw);
+}
+
+#[tokio::test]
+async fn migrate_workflow_state_leaves_a_migrated_workflow_untouched() {
+    let mut env = with_legacy_workflow("in_progress", None, None).await;
+    let workflow = env.addresses().workflow_state;
+
+    let migrate = env.migrate(&env.stranger);
+    process(&mut env.context, migrate, &[&env.stranger]).await.unwrap();
+    let migrated = env.account(&workflow).await.unwrap();
+    let balance = env.lamports(&env.admin.pubkey()).await;
+
+    let migrate = env.migrate(&env.admin);
+    process(&mut env.context, migrate, &[&env.admin]).await.unwrap();
+
+    assert_eq!(env.account(&workflow).await.unwrap(), migrated);
+    assert_eq!(env.lamports(&env.admin.pubkey()).await, balance);
+}
+
+#[tokio::test]
+async fn migrate_workflow_state_archives_an_unknown_legacy_status() {
+    let mut env = with_legacy_workflow("hired", None, None).await;
+    let workflow = env.addresses().workflow_state;
+
+    let migrate = env.migrate(&env.stranger);
+    process(&mut env.context, migrate, &[&env.stranger]).await.unwrap();
+
+    let workflow_state_data = env.workflow_state().await;
+    assert_eq!(workflow_state_data.status, WorkflowStatus::Saved);
+    assert!(workflow_state_data.archived);
+
+    //so the candidate can reclaim its rent
+    let close = env.close(&env.candidate, &env.candidate.pubkey());
+    process(&mut env.context, close, &[&env.candidate]).await.unwrap();
+    assert_eq!(env.account(&workflow).await, None);
+}
+
+#[tokio::test]
+async fn migrate_workflow_state_rejects_an_account_off_the_workflow_pda() {
+    let address = Pubkey::new_unique();
+    let mut env = with_legacy_workflow("applied", Some(address), None).await;
+
+    let migrate = instruction::migrate_workflow_state(&env.program_id, &env.stranger.pubkey(), &address);
+    assert_workflow_error(process(&mut env.context, migrate, &[&env.stranger]).await, WorkflowError::WorkflowPdaMismatch);
+}
+
+#[tokio::test]
+async fn migrate_workflow_state_rejects_an_account_of_another_program() {
+    let mut env = with_legacy_workflow("applied", None, Some(Pubkey::new_unique())).await;
+
+    let migrate = env.migrate(&env.stranger);
+    assert_workflow_error(process(&mut env.context, migrate, &[&env.stranger]).await, WorkflowError::WorkflowNotOwnedByProgram);
+}
+
+#[tokio::test]
+async fn migrate_workflow_state_rejects_an_unknown_layout_version() {
+    let mut env = with_preloaded_workflow(|state| {
+        WorkflowState {
+            version: WorkflowState::VERSION + 1,
+            ..state
+        }
+        .try_to_vec()
+        .unwrap()
+    })
+    .await;
+
+    let migrate = env.migrate(&env.stranger);
+    assert_workflow_error(process(&mut env.context, migrate, &[&env.stranger]).await, WorkflowError::UnsupportedWorkflowVersion);
+}
+
+// Events
+
+fn workflow_keys(addresses: &WorkflowAddresses) -> WorkflowKeys {
+    WorkflowKeys {
+        workflow_pubkey: addresses.workflow_state,
+        job_pubkey: addresses.jobpost_info_state,
+        company_pubkey: addresses.company_info_state,
+        user_pubkey: addresses.applicant_info_state,
+    }
+}
+
+#[tokio::test]
+async fn add_workflow_state_logs_the_created_application() {
+    let mut env = TestEnv::with_siblings().await;
+
+    let add = env.add(WorkflowStatus::Applied);
+    let events = process_with_events(&mut env.context, add, &[&env.candidate]).await;
+
+    let expected = WorkflowEvent::ApplicationCreated {
+        keys: workflow_keys(&env.addresses()),
+        status: WorkflowStatus::Applied,
+        actor: env.candidate.pubkey(),
+        timestamp: env.workflow_state().await.updated_at,
+    };
+    assert_eq!(events, vec![expected]);
+}
+
+#[tokio::test]
+async fn update_workflow_state_logs_the_status_change() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let update = env.update(&env.company_owner, WorkflowStatus::InProgress);
+    let events = process_with_events(&mut env.context, update, &[&env.company_owner]).await;
+
+    let expected = WorkflowEvent::StatusChanged {
+        keys: workflow_keys(&env.addresses()),
+        old_status: WorkflowStatus::Applied,
+        new_status: WorkflowStatus::InProgress,
+        archived: false,
+        is_saved: false,
+        actor: env.company_owner.pubkey(),
+        timestamp: env.workflow_state().await.updated_at,
+    };
+    assert_eq!(events, vec![expected]);
+}
+
+#[tokio::test]
+async fn update_workflow_payment_state_logs_the_recorded_payment() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let update_payment = env.update_payment(&env.payment_authority, APPLICATION_PRICE);
+    let events = process_with_events(&mut env.context, update_payment, &[&env.payment_authority]).await;
+
+    let expected = WorkflowEvent::PaymentRecorded {
+        keys: workflow_keys(&env.addresses()),
+        is_paid: true,
+        amount: APPLICATION_PRICE,
+        mint: Pubkey::default(),
+        actor: env.payment_authority.pubkey(),
+        timestamp: env.workflow_state().await.updated_at,
+    };
+    assert_eq!(events, vec![expected]);
+}
+
+#[tokio::test]
+async fn pay_for_application_logs_the_received_payment() {
+    let mut env = TestEnv::with_workflow(WorkflowStatus::Applied).await;
+
+    let pay = env.pay(APPLICATION_PRICE);
+    let events = process_with_events(&mut env.context, pay, &[&env.company_owner]).await;
+
+    let expected = WorkflowEvent::PaymentRecorded {
+        keys: workflow_keys(&env.addresses()),
+        is_paid: true,
+        amount: APPLICATION_PRICE,
+        mint: Pubkey::default(),
+        actor: env.company_owner.pubkey(),
+        timestamp: env.workflow_state().await.paid_at,
+    };
+    assert_eq!(events, vec![expected]);
+}
+
+#[tokio::test]
+async fn close_workflow_state_logs_the_closed_workflow() {
+    let mut env = with_workflow_in(WorkflowStatus::Withdraw).await;
+    let recipient = env.candidate.pubkey();
+
+    let close = env.close(&env.candidate, &recipient);
+    let events = process_with_events(&mut env.context, close, &[&env.candidate]).await;
+
+    let expected = WorkflowEvent::WorkflowClosed {
+        keys: workflow_keys(&env.addresses()),
+        status: WorkflowStatus::Withdraw,
+        recipient,
+        actor: env.candidate.pubkey(),
+    };
+    assert_eq!(events, vec![expected]);
+}
+
+#[tokio::test]
+async fn migrate_workflow_state_logs_the_migration_once() {
+    let mut env = with_legacy_workflow("applied", None, None).await;
+
+    let migrate = env.migrate(&env.stranger);
+    let events = process_with_events(&mut env.context, migrate, &[&env.stranger]).await;
+
+    let expected = WorkflowEvent::WorkflowMigrated {
+        workflow_pubkey: env.addresses().workflow_state,
+        from_version: WorkflowStateV1::VERSION,
+        to_version: WorkflowState::VERSION,
+    };
+    assert_eq!(events, vec![expected]);
+
+    //a second migration of the current layout logs nothing
+    let migrate = env.migrate(&env.candidate);
+    assert_eq!(process_with_events(&mut env.context, migrate, &[&env.candidate]).await, vec![]);
+}